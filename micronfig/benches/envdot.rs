@@ -0,0 +1,85 @@
+//! Benchmarks comparing the `.env` parser with the regex-based one it replaced.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use micronfig::envdot::{DotEnv, EnvDot};
+use regex::Regex;
+
+/// Generate the contents of a `.env` file with the given number of variables, mixing the most common syntaxes.
+fn generate(variables: usize) -> String {
+	let mut contents = String::new();
+
+	for index in 0..variables {
+		match index % 4 {
+			0 => contents.push_str(&format!("# Variable number {index}\nUNQUOTED_{index}=value_{index}\n")),
+			1 => contents.push_str(&format!("export SINGLE_{index}='single quoted {index}'\n")),
+			2 => contents.push_str(&format!("DOUBLE_{index}=\"double quoted {index}\" # comment\n")),
+			_ => contents.push_str(&format!("SPACED_{index} = spaced {index}\n")),
+		}
+	}
+
+	contents
+}
+
+/// Generate the contents of a `.env` file with a double-quoted value spanning the given number of lines, each containing escaped quotes, followed by a quote left unterminated before the given number of variables.
+fn generate_multiline(lines: usize) -> String {
+	let mut contents = String::from("MULTILINE=\"");
+	for index in 0..lines {
+		contents.push_str(&format!("line \\\"{index}\\\"\n"));
+	}
+	contents.push_str("\"\nUNTERMINATED=`\n");
+	contents.push_str(&generate(lines));
+	contents
+}
+
+/// The regex-based parser used before the `envdot` feature stopped depending on the `regex` crate, kept as a baseline.
+fn parse_regex(contents: &str) -> HashMap<OsString, String> {
+	let re = Regex::new(r#"^\s*(?:export\s)?\s*([^=]+?)\s*=\s*(.+)\s*$"#)
+		.expect("Regex to be valid");
+
+	contents.split('\n')
+		.filter_map(|line| re.captures(line))
+		.map(|capture| {
+			let key = &capture[1];
+			let value = &capture[2];
+
+			let value = value.strip_prefix('\'').and_then(|value| value.strip_suffix('\''))
+				.or_else(|| value.strip_prefix('"').and_then(|value| value.strip_suffix('"')))
+				.unwrap_or(value);
+
+			(key.into(), value.to_owned())
+		})
+		.collect()
+}
+
+/// The current parser, as used by [`micronfig::envdot::parse_dotenv`].
+fn parse_stream(contents: &str) -> DotEnv {
+	EnvDot::try_read(contents.as_bytes(), ".env")
+		.expect("reading from a string to never fail")
+		.values()
+}
+
+fn bench_parse(c: &mut Criterion) {
+	let mut group = c.benchmark_group("envdot");
+
+	for variables in [10, 1_000, 100_000] {
+		let contents = generate(variables);
+		group.throughput(Throughput::Bytes(contents.len() as u64));
+
+		group.bench_with_input(BenchmarkId::new("regex", variables), &contents, |b, contents| b.iter(|| parse_regex(contents)));
+		group.bench_with_input(BenchmarkId::new("stream", variables), &contents, |b, contents| b.iter(|| parse_stream(contents)));
+	}
+
+	for lines in [10, 1_000, 100_000] {
+		let contents = generate_multiline(lines);
+		group.throughput(Throughput::Bytes(contents.len() as u64));
+
+		group.bench_with_input(BenchmarkId::new("stream_multiline", lines), &contents, |b, contents| b.iter(|| parse_stream(contents)));
+	}
+
+	group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
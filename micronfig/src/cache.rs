@@ -1,118 +1,511 @@
-//! **Private**; definition of [`Cache`].
+//! **Private**; definition of [`Cache`] and [`CacheBuilder`].
 
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
+use std::sync::Arc;
+use crate::Error;
+use crate::source::{FromRaw, Provenance, Raw, Source, Trim};
 
 /// Cache initialized only once per config block and used to quickly retrieve configuration values.
 ///
-/// Every `env*` feature has its own field here, which may or may not be used.
-#[derive(Clone, Default, Debug)]
+/// Holds an ordered list of [`Source`]s, which are checked one after the other until one of them has a value for the requested key.
+#[derive(Clone, Debug)]
 pub struct Cache {
-	/// Unused.
-	#[cfg(feature = "envvars")]
-	pub envvars: (),
+	/// The registered sources, in order of access priority.
+	sources: Vec<Arc<dyn Source>>,
+}
 
-	/// Unused.
-	#[cfg(feature = "envfiles")]
-	pub envfiles: (),
+impl Default for Cache {
+	/// Create a cache with the sources of all enabled `env*` features, but without any `.env` file registered.
+	fn default() -> Self {
+		let mut this = Self { sources: Vec::new() };
 
-	/// `.env` file cache, in order of access priority.
-	///
-	/// More can be added with [`Cache::envdot_register`].
-	#[cfg(feature = "envdot")]
-	pub envdot: Vec<crate::envdot::DotEnv>,
+		this.init_envfiles();
+		this.init_credentials();
+		this.init_envvars();
+		this.init_envdir();
+
+		this
+	}
 }
 
 impl Cache {
+	/// Create a [`CacheBuilder`], allowing to choose which sources to register and in what order.
+	pub fn builder() -> CacheBuilder {
+		CacheBuilder::new()
+	}
+
 	/// Initialize a new cache.
 	pub fn new() -> Self {
 		let mut this = Self::default();
 
-		this.init_envfiles();
-		this.init_envvars();
 		this.init_envdot();
 
 		this
 	}
 
 	#[cfg(feature = "envfiles")]
-	fn init_envfiles(&mut self) {}
+	fn init_envfiles(&mut self) {
+		self.register(crate::envfiles::EnvFiles::default());
+	}
 	#[cfg(not(feature = "envfiles"))]
 	fn init_envfiles(&mut self) {}
 
+	#[cfg(feature = "credentials")]
+	fn init_credentials(&mut self) {
+		self.register(crate::credentials::Credentials::default());
+	}
+	#[cfg(not(feature = "credentials"))]
+	fn init_credentials(&mut self) {}
+
 	#[cfg(feature = "envvars")]
-	fn init_envvars(&mut self) {}
+	fn init_envvars(&mut self) {
+		self.register(crate::envvars::EnvVars);
+	}
 	#[cfg(not(feature = "envvars"))]
 	fn init_envvars(&mut self) {}
 
+	#[cfg(feature = "envdir")]
+	fn init_envdir(&mut self) {
+		self.register(crate::envdir::EnvDir::default());
+	}
+	#[cfg(not(feature = "envdir"))]
+	fn init_envdir(&mut self) {}
+
 	#[cfg(feature = "envdot")]
 	fn init_envdot(&mut self) {
-		self.envdot_register("./.env.local");
-		self.envdot_register("./.env");
+		self.init_envdot_discovered(&crate::envdot::Discovery::WorkingDirectory);
 	}
 	#[cfg(not(feature = "envdot"))]
 	fn init_envdot(&mut self) {}
 
+	#[cfg(feature = "envdot")]
+	fn init_envdot_discovered(&mut self, discovery: &crate::envdot::Discovery) {
+		let profile = crate::envdot::profile(crate::envdot::PROFILE_VARIABLE);
+		for path in crate::envdot::cascade(&discovery.directory(), profile.as_deref()) {
+			self.envdot_register(path);
+		}
+	}
+
+	/// Initialize a new cache like [`Cache::new`] does, but looking for `.env` files as specified by the given [`Discovery`](crate::envdot::Discovery).
+	#[cfg(feature = "envdot")]
+	pub fn discover(discovery: crate::envdot::Discovery) -> Self {
+		let mut this = Self::default();
+
+		this.init_envdot_discovered(&discovery);
+
+		this
+	}
+
+	/// Register a new [`Source`] in the cache, with a lower priority than all the already registered ones.
+	pub fn register<S>(&mut self, source: S)
+		where S: Source + 'static
+	{
+		self.sources.push(Arc::new(source));
+	}
+
 	/// Register a new `.env` file in the cache, if it exists.
+	///
+	/// Problems encountered while loading it are handled leniently, as specified by [`Strictness::default`](crate::envdot::Strictness::default).
 	#[cfg(feature = "envdot")]
 	pub fn envdot_register<Path>(&mut self, path: Path)
 		where Path: AsRef<std::path::Path> + Debug
 	{
-		let dotenv = crate::envdot::parse_dotenv(path);
-		if let Some(dotenv) = dotenv {
-			self.envdot.push(dotenv);
+		if let Some(source) = crate::envdot::Strictness::default().load(path) {
+			self.sources.push(source);
 		}
 	}
 
+	/// The registered sources, in order of access priority.
+	pub fn sources(&self) -> &[Arc<dyn Source>] {
+		&self.sources
+	}
+
 	/// Get a value from the cache.
 	///
-	/// The following sources, if the respective feature is enabled, are checked in the following order:
+	/// The registered sources are checked in order of priority; by default, the following ones are registered, if the respective feature is enabled:
 	/// 1. `envfiles`
-	/// 2. `envvars`
-	/// 3. `envdot`
+	/// 2. `credentials`
+	/// 3. `envvars`
+	/// 4. `envdir`
+	/// 5. `envdot`
+	///
+	/// # Panics
+	///
+	/// If one of the sources fails to read the value; see [`Cache::try_get`] for a non-panicking alternative.
 	///
 	pub fn get(&self, key: &OsStr) -> Option<String>
 	{
-		let mut value = None;
+		self.try_get(key)
+			.unwrap_or_else(|err| panic!("{err}"))
+	}
+
+	/// Get a value from the cache, returning an [`Error`] if one of the sources fails to read it.
+	///
+	/// Sources are checked in the same order as [`Cache::get`].
+	pub fn try_get(&self, key: &OsStr) -> Result<Option<String>, Error>
+	{
+		Ok(self.try_lookup(key)?.map(|(value, _)| value))
+	}
 
-		if value.is_none() { value = self.get_from_envfiles(key); }
-		if value.is_none() { value = self.get_from_envvars(key); }
-		if value.is_none() { value = self.get_from_envdot(key); }
+	/// Get a value from the cache along with its [`Provenance`], returning an [`Error`] if one of the sources fails to read it.
+	///
+	/// Sources are checked in the same order as [`Cache::get`].
+	///
+	/// Values which are not valid UTF-8 cause an [`Error::Source`]; see [`Cache::try_lookup_raw`] to retrieve them anyway.
+	pub fn try_lookup(&self, key: &OsStr) -> Result<Option<(String, Provenance)>, Error>
+	{
+		into_string(key, self.try_lookup_raw(key)?)
+	}
+
+	/// Like [`Cache::try_lookup`], but asking file-based sources to trim the value as specified by `trim`, instead of as they were configured to.
+	pub fn try_lookup_trimmed(&self, key: &OsStr, trim: Trim) -> Result<Option<(String, Provenance)>, Error>
+	{
+		into_string(key, self.try_lookup_raw_trimmed(key, trim)?)
+	}
+
+	/// Like [`Cache::try_lookup`], but returning the value as [`Raw`], without requiring it to be valid UTF-8.
+	pub fn try_lookup_raw(&self, key: &OsStr) -> Result<Option<(Raw, Provenance)>, Error>
+	{
+		let mut resolver = Resolver { cache: self, stack: Vec::new() };
+		self.lookup_skipping(key, None, None, &mut resolver)
+	}
+
+	/// Like [`Cache::try_lookup_raw`], but asking file-based sources to trim the value as specified by `trim`, like [`Cache::try_lookup_trimmed`] does.
+	pub fn try_lookup_raw_trimmed(&self, key: &OsStr, trim: Trim) -> Result<Option<(Raw, Provenance)>, Error>
+	{
+		let mut resolver = Resolver { cache: self, stack: Vec::new() };
+		self.lookup_skipping(key, None, Some(trim), &mut resolver)
+	}
+
+	/// Like [`Cache::try_lookup_raw`], but converting the value to `T` via [`FromRaw`], and asking file-based sources to trim it as specified by `trim`, or by [`FromRaw::TRIM`] if [`None`].
+	///
+	/// Values which cannot be represented by `T` cause an [`Error::Source`].
+	pub fn try_lookup_as<T>(&self, key: &OsStr, trim: Option<Trim>) -> Result<Option<(T, Provenance)>, Error>
+		where T: FromRaw
+	{
+		let mut resolver = Resolver { cache: self, stack: Vec::new() };
+		let (raw, provenance) = match self.lookup_skipping(key, None, trim.or(T::TRIM), &mut resolver)? {
+			Some(value) => value,
+			None => return Ok(None),
+		};
+
+		match T::from_raw(raw) {
+			Ok(value) => Ok(Some((value, provenance))),
+			Err(err) => Err(Error::Source { key: key.to_string_lossy().into_owned(), provenance: Box::new(provenance), source: err }),
+		}
+	}
+
+	/// Like [`Cache::try_lookup_raw`], but skipping the source at the given address, if any, and passing `trim` to [`Source::lookup_raw_with`].
+	fn lookup_skipping(&self, key: &OsStr, skip: Option<*const ()>, trim: Option<Trim>, resolver: &mut Resolver<'_>) -> Result<Option<(Raw, Provenance)>, Error>
+	{
+		for source in self.sources.iter() {
+			if skip == Some(Arc::as_ptr(source) as *const ()) {
+				continue
+			}
+
+			let value = source.lookup_raw_with(key, trim, resolver)
+				.map_err(|err| Error::Source { key: key.to_string_lossy().into_owned(), provenance: Box::new(source.provenance()), source: err })?;
+
+			if value.is_some() {
+				return Ok(value)
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// The provenances of all registered sources, in order of access priority.
+	pub fn provenances(&self) -> Vec<Provenance> {
+		self.sources.iter()
+			.map(|source| source.provenance())
+			.collect()
+	}
+}
+
+/// Convert the value retrieved for the given key to a [`String`], returning an [`Error::Source`] if it is not valid UTF-8.
+fn into_string(key: &OsStr, value: Option<(Raw, Provenance)>) -> Result<Option<(String, Provenance)>, Error> {
+	let (value, provenance) = match value {
+		Some(value) => value,
+		None => return Ok(None),
+	};
+
+	match value.into_string() {
+		Ok(value) => Ok(Some((value, provenance))),
+		Err(err) => Err(Error::Source { key: key.to_string_lossy().into_owned(), provenance: Box::new(provenance), source: err }),
+	}
+}
+
+/// Context allowing [`Source`]s to retrieve the values of other variables from the [`Cache`] they are registered in, for example to expand references in `.env` files.
+///
+/// Passed to [`Source::lookup_with`].
+#[derive(Debug)]
+pub struct Resolver<'c> {
+	/// The cache the variables are retrieved from.
+	cache: &'c Cache,
+
+	/// The variables currently being resolved, each paired with the address of the source which requested them.
+	stack: Vec<(OsString, *const ())>,
+}
+
+impl Resolver<'_> {
+	/// Create a resolver not attached to any [`Cache`], which never finds any variable.
+	pub(crate) fn detached() -> Self {
+		static EMPTY: Cache = Cache { sources: Vec::new() };
+		Resolver { cache: &EMPTY, stack: Vec::new() }
+	}
+
+	/// Get the value of the given variable from the cache on behalf of the given source, which is skipped.
+	///
+	/// Returns an [`std::io::Error`] if the variable is already being resolved on behalf of the same source, as that would cause an infinite loop, or if any source fails to read the value.
+	pub fn resolve(&mut self, key: &OsStr, from: &dyn Source) -> std::io::Result<Option<String>> {
+		let from = from as *const dyn Source as *const ();
+
+		if self.stack.iter().any(|(resolving, source)| resolving == key && *source == from) {
+			let cycle: Vec<String> = self.stack.iter()
+				.map(|(resolving, _)| resolving.to_string_lossy().into_owned())
+				.chain(std::iter::once(key.to_string_lossy().into_owned()))
+				.collect();
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("reference cycle detected: {}", cycle.join(" -> "))))
+		}
+
+		let cache = self.cache;
+		self.stack.push((key.to_os_string(), from));
+		let value = cache.lookup_skipping(key, Some(from), None, self);
+		self.stack.pop();
 
 		value
+			.map_err(std::io::Error::other)?
+			.map(|(value, _)| value.into_string())
+			.transpose()
+	}
+}
+
+/// Builder for a [`Cache`] with a custom set of sources, in a custom priority order.
+///
+/// Sources are registered in the order the methods are called, each one with a lower priority than the ones registered before it.
+///
+/// ```
+/// # if cfg!(all(feature = "envvars", feature = "envdot")) {
+/// // Let `.env` override the process environment, and ignore `_FILE` variables.
+/// let cache = micronfig::cache::Cache::builder()
+/// 	.envdot_cwd()
+/// 	.envvars()
+/// 	.build();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CacheBuilder {
+	/// The sources registered so far, in order of access priority.
+	sources: Vec<Pending>,
+
+	/// How to handle problems with the `.env` files registered.
+	#[cfg(feature = "envdot")]
+	strictness: crate::envdot::Strictness,
+
+	/// How file-based sources should trim the contents of the files they read.
+	trim: Trim,
+
+	/// The environment variable to read the profile from, if not the default one.
+	#[cfg(feature = "envdot")]
+	profile_variable: Option<String>,
+}
+
+/// A source registered in a [`CacheBuilder`], which might still have to be loaded.
+#[derive(Clone, Debug)]
+enum Pending {
+	/// A source ready to be used.
+	Source(Arc<dyn Source>),
+
+	/// The `envfiles` source, which is created only when the [`Cache`] is built, so that [`CacheBuilder::trim`] applies to it regardless of the order it was called in.
+	#[cfg(feature = "envfiles")]
+	EnvFiles,
+
+	/// The `credentials` source, which is completed only when the [`Cache`] is built, like [`Pending::EnvFiles`].
+	#[cfg(feature = "credentials")]
+	Credentials(crate::credentials::Credentials),
+
+	/// The `envdir` source, which is completed only when the [`Cache`] is built, like [`Pending::EnvFiles`].
+	#[cfg(feature = "envdir")]
+	EnvDir(crate::envdir::EnvDir),
+
+	/// A directory whose `.env` files should be registered according to [`cascade`](crate::envdot::cascade), which is discovered and resolved only when the [`Cache`] is built, like [`Pending::EnvDot`].
+	#[cfg(feature = "envdot")]
+	EnvDotCascade(crate::envdot::Discovery),
+
+	/// A `.env` file, which is loaded only when the [`Cache`] is built, so that [`CacheBuilder::strict`] and [`CacheBuilder::lenient`] apply to it regardless of the order they were called in.
+	#[cfg(feature = "envdot")]
+	EnvDot(std::path::PathBuf),
+}
+
+impl CacheBuilder {
+	/// Create a builder with no sources registered.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register the given [`Source`].
+	pub fn source<S>(mut self, source: S) -> Self
+		where S: Source + 'static
+	{
+		self.sources.push(Pending::Source(Arc::new(source)));
+		self
 	}
 
+	/// Register the `envfiles` source.
 	#[cfg(feature = "envfiles")]
-	pub fn get_from_envfiles(&self, key: &OsStr) -> Option<String> {
-		crate::envfiles::get(key)
+	pub fn envfiles(mut self) -> Self {
+		self.sources.push(Pending::EnvFiles);
+		self
 	}
-	#[cfg(not(feature = "envfiles"))]
-	pub fn get_from_envfiles(&self, _key: &OsStr) -> Option<String> {
-		None
+
+	/// Register the `credentials` source, looking for credentials named exactly like the keys.
+	#[cfg(feature = "credentials")]
+	pub fn credentials(self) -> Self {
+		self.credentials_mapped(crate::credentials::Mapping::default())
 	}
 
+	/// Register the `credentials` source, looking for credentials named after the keys as specified by the given [`Mapping`](crate::credentials::Mapping).
+	#[cfg(feature = "credentials")]
+	pub fn credentials_mapped(mut self, mapping: crate::credentials::Mapping) -> Self {
+		self.sources.push(Pending::Credentials(crate::credentials::Credentials::new(mapping)));
+		self
+	}
+
+	/// Register the `credentials` source, looking for credentials named after the keys as specified by the given [`Mapping`](crate::credentials::Mapping), and then as specified by the `fallback` one if none is found.
+	#[cfg(feature = "credentials")]
+	pub fn credentials_fallback(mut self, mapping: crate::credentials::Mapping, fallback: crate::credentials::Mapping) -> Self {
+		self.sources.push(Pending::Credentials(crate::credentials::Credentials::new(mapping).with_fallback(fallback)));
+		self
+	}
+
+	/// Register the `envvars` source.
 	#[cfg(feature = "envvars")]
-	pub fn get_from_envvars(&self, key: &OsStr) -> Option<String> {
-		crate::envvars::get(key)
+	pub fn envvars(self) -> Self {
+		self.source(crate::envvars::EnvVars)
 	}
-	#[cfg(not(feature = "envvars"))]
-	pub fn get_from_envvars(&self, _key: &OsStr) -> Option<String> {
-		None
+
+	/// Register the `envdir` source, looking for files named exactly like the keys in the given directories.
+	#[cfg(feature = "envdir")]
+	pub fn envdir<I, P>(self, directories: I) -> Self
+		where I: IntoIterator<Item = P>, P: Into<std::path::PathBuf>
+	{
+		self.envdir_mapped(directories, crate::envdir::Mapping::default())
 	}
 
+	/// Register the `envdir` source, looking for files named after the keys as specified by the given [`Mapping`](crate::envdir::Mapping) in the given directories.
+	#[cfg(feature = "envdir")]
+	pub fn envdir_mapped<I, P>(mut self, directories: I, mapping: crate::envdir::Mapping) -> Self
+		where I: IntoIterator<Item = P>, P: Into<std::path::PathBuf>
+	{
+		self.sources.push(Pending::EnvDir(crate::envdir::EnvDir::new(directories).with_mapping(mapping)));
+		self
+	}
+
+	/// Register the `envdir` source for [`DEFAULT_DIRECTORY`](crate::envdir::DEFAULT_DIRECTORY), like [`Cache::new`] does.
+	#[cfg(feature = "envdir")]
+	pub fn envdir_secrets(self) -> Self {
+		self.envdir([crate::envdir::DEFAULT_DIRECTORY])
+	}
+
+	/// Register the `.env` file at the given path, if it exists.
 	#[cfg(feature = "envdot")]
-	pub fn get_from_envdot(&self, key: &OsStr) -> Option<String> {
-		for dotenv in self.envdot.iter() {
-			let value = crate::envdot::get(dotenv, key);
-			if value.is_some() {
-				return value
+	pub fn envdot<Path>(mut self, path: Path) -> Self
+		where Path: AsRef<std::path::Path> + Debug
+	{
+		self.sources.push(Pending::EnvDot(path.as_ref().to_path_buf()));
+		self
+	}
+
+	/// Register the `.env` files in the given directory for the current profile, if they exist, as specified by [`cascade`](crate::envdot::cascade).
+	///
+	/// The profile is read from the variable set with [`CacheBuilder::profile_variable`], or from [`PROFILE_VARIABLE`](crate::envdot::PROFILE_VARIABLE) by default.
+	#[cfg(feature = "envdot")]
+	pub fn envdot_cascade<Path>(self, directory: Path) -> Self
+		where Path: AsRef<std::path::Path>
+	{
+		self.envdot_discover(crate::envdot::Discovery::Directory(directory.as_ref().to_path_buf()))
+	}
+
+	/// Register the `.env` files in the directory found with the given [`Discovery`](crate::envdot::Discovery) for the current profile, if they exist, like [`CacheBuilder::envdot_cascade`] does.
+	#[cfg(feature = "envdot")]
+	pub fn envdot_discover(mut self, discovery: crate::envdot::Discovery) -> Self {
+		self.sources.push(Pending::EnvDotCascade(discovery));
+		self
+	}
+
+	/// Register the `.env` files in the current working directory for the current profile, if they exist, like [`Cache::new`] does.
+	#[cfg(feature = "envdot")]
+	pub fn envdot_cwd(self) -> Self {
+		self.envdot_discover(crate::envdot::Discovery::WorkingDirectory)
+	}
+
+	/// Read the profile used by [`CacheBuilder::envdot_cascade`] from the given environment variable.
+	#[cfg(feature = "envdot")]
+	pub fn profile_variable(mut self, variable: impl Into<String>) -> Self {
+		self.profile_variable = Some(variable.into());
+		self
+	}
+
+	/// Trim the contents of the files read by file-based sources as specified, instead of as specified by [`Trim::default`].
+	pub fn trim(mut self, trim: Trim) -> Self {
+		self.trim = trim;
+		self
+	}
+
+	/// Make `.env` files which cannot be read or contain malformed lines fail every lookup reaching them, see [`Strictness::Strict`](crate::envdot::Strictness::Strict).
+	#[cfg(feature = "envdot")]
+	pub fn strict(mut self) -> Self {
+		self.strictness = crate::envdot::Strictness::Strict;
+		self
+	}
+
+	/// Report problems with `.env` files to the given hook, and skip the malformed lines, see [`Strictness::Lenient`](crate::envdot::Strictness::Lenient).
+	///
+	/// By default, problems are not reported at all; for example, to print them as warnings instead:
+	///
+	/// ```
+	/// # #[cfg(feature = "envdot")]
+	/// let cache = micronfig::cache::Cache::builder()
+	/// 	.envdot_cwd()
+	/// 	.lenient(|error| eprintln!("warning: {error}"))
+	/// 	.build();
+	/// ```
+	#[cfg(feature = "envdot")]
+	pub fn lenient<F>(mut self, hook: F) -> Self
+		where F: Fn(&crate::envdot::DotEnvError) + Send + Sync + 'static
+	{
+		self.strictness = crate::envdot::Strictness::Lenient(Arc::new(hook));
+		self
+	}
+
+	/// Create the [`Cache`], loading the registered `.env` files.
+	pub fn build(self) -> Cache {
+		#[cfg(feature = "envdot")]
+		let profile = crate::envdot::profile(self.profile_variable.as_deref().unwrap_or(crate::envdot::PROFILE_VARIABLE));
+
+		let mut sources = Vec::new();
+		for pending in self.sources {
+			match pending {
+				Pending::Source(source) => sources.push(source),
+				#[cfg(feature = "envfiles")]
+				Pending::EnvFiles => sources.push(Arc::new(crate::envfiles::EnvFiles::new(self.trim))),
+				#[cfg(feature = "credentials")]
+				Pending::Credentials(credentials) => sources.push(Arc::new(credentials.with_trim(self.trim))),
+				#[cfg(feature = "envdir")]
+				Pending::EnvDir(envdir) => sources.push(Arc::new(envdir.with_trim(self.trim))),
+				#[cfg(feature = "envdot")]
+				Pending::EnvDot(path) => sources.extend(self.strictness.load(path)),
+				#[cfg(feature = "envdot")]
+				Pending::EnvDotCascade(discovery) => {
+					for path in crate::envdot::cascade(&discovery.directory(), profile.as_deref()) {
+						sources.extend(self.strictness.load(path));
+					}
+				},
 			}
 		}
-		None
-	}
-	#[cfg(not(feature = "envdot"))]
-	pub fn get_from_envdot(&self, _key: &OsStr) -> Option<String> {
-		None
+
+		Cache { sources }
 	}
 }
 
@@ -135,9 +528,10 @@ mod tests {
 		);
 
 		let mut cache = Cache::default();
+		let before = cache.sources().len();
 		cache.envdot_register(file.as_os_str());
 
-		assert_eq!(cache.envdot.len(), 1);
+		assert_eq!(cache.sources().len(), before + 1);
 	}
 
 	#[cfg(feature = "envvars")]
@@ -209,4 +603,302 @@ mod tests {
 		assert_eq!(cache.get("ENVDOT".as_ref()), Some("envdot".to_string()));
 		assert_eq!(cache.get("NONE".as_ref()), None);
 	}
+
+	#[cfg(feature = "envfiles")]
+	#[test]
+	fn try_get_envfiles_missing() {
+		std::env::remove_var("MISSING");
+		std::env::set_var("MISSING_FILE", "/this/file/does/not/exist");
+
+		let cache = Cache::default();
+		let error = cache.try_get("MISSING".as_ref()).unwrap_err();
+		assert!(matches!(error, Error::Source { .. }));
+		assert_eq!(error.key(), "MISSING");
+	}
+
+	#[test]
+	fn register() {
+		#[derive(Debug)]
+		struct Constant;
+
+		impl Source for Constant {
+			fn name(&self) -> String {
+				"constant".to_string()
+			}
+
+			fn get(&self, _key: &OsStr) -> std::io::Result<Option<String>> {
+				Ok(Some("constant".to_string()))
+			}
+		}
+
+		std::env::remove_var("REGISTER");
+		std::env::remove_var("REGISTER_FILE");
+
+		let mut cache = Cache::default();
+		cache.register(Constant);
+		assert_eq!(cache.get("REGISTER".as_ref()), Some("constant".to_string()));
+		assert_eq!(cache.sources().last().unwrap().name(), "constant");
+	}
+
+	#[test]
+	fn builder_empty() {
+		std::env::set_var("BUILDER_EMPTY", "garas");
+
+		let cache = Cache::builder().build();
+		assert!(cache.sources().is_empty());
+		assert_eq!(cache.get("BUILDER_EMPTY".as_ref()), None);
+	}
+
+	#[test]
+	#[cfg(all(feature = "envdot", feature = "envvars"))]
+	fn builder_priority() {
+		let envdot_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				export BUILDER_BOTH=envdot
+				export BUILDER_ENVDOT=envdot
+			"#
+		);
+
+		std::env::set_var("BUILDER_BOTH", "envvars");
+		std::env::remove_var("BUILDER_ENVDOT");
+
+		let envvars_first = Cache::builder()
+			.envvars()
+			.envdot(envdot_file.as_os_str())
+			.build();
+		assert_eq!(envvars_first.get("BUILDER_BOTH".as_ref()), Some("envvars".to_string()));
+		assert_eq!(envvars_first.get("BUILDER_ENVDOT".as_ref()), Some("envdot".to_string()));
+
+		let envdot_first = Cache::builder()
+			.envdot(envdot_file.as_os_str())
+			.envvars()
+			.build();
+		assert_eq!(envdot_first.get("BUILDER_BOTH".as_ref()), Some("envdot".to_string()));
+		assert_eq!(envdot_first.get("BUILDER_ENVDOT".as_ref()), Some("envdot".to_string()));
+	}
+
+	#[test]
+	#[cfg(all(feature = "envdot", feature = "envvars"))]
+	fn try_lookup_provenance() {
+		let envdot_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				export LOOKUP_ENVDOT=envdot
+			"#
+		);
+
+		std::env::set_var("LOOKUP_ENVVARS", "envvars");
+		std::env::remove_var("LOOKUP_ENVDOT");
+
+		let cache = Cache::builder()
+			.envvars()
+			.envdot(envdot_file.as_os_str())
+			.build();
+
+		let (_, provenance) = cache.try_lookup("LOOKUP_ENVVARS".as_ref()).unwrap().unwrap();
+		assert_eq!(provenance, Provenance::new("envvars"));
+
+		let (_, provenance) = cache.try_lookup("LOOKUP_ENVDOT".as_ref()).unwrap().unwrap();
+		assert_eq!(provenance, Provenance::new("envdot").with_path(envdot_file.as_os_str()).with_line(2));
+	}
+
+	#[test]
+	#[cfg(feature = "envdot")]
+	fn interpolation_across_files() {
+		let local_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				INTERPOLATION_URL=http://${INTERPOLATION_HOST}:${INTERPOLATION_PORT:-80}/
+			"#
+		);
+		let shared_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				INTERPOLATION_HOST=example.org
+			"#
+		);
+
+		std::env::remove_var("INTERPOLATION_HOST");
+		std::env::remove_var("INTERPOLATION_PORT");
+
+		let cache = Cache::builder()
+			.envdot(local_file.as_os_str())
+			.envdot(shared_file.as_os_str())
+			.build();
+
+		assert_eq!(cache.get("INTERPOLATION_URL".as_ref()), Some("http://example.org:80/".to_string()));
+	}
+
+	#[test]
+	#[cfg(feature = "envdot")]
+	fn interpolation_process_environment() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				INTERPOLATION_GREETING="hello ${INTERPOLATION_NAME}"
+			"#
+		);
+
+		std::env::set_var("INTERPOLATION_NAME", "world");
+
+		let cache = Cache::builder()
+			.envdot(file.as_os_str())
+			.build();
+
+		assert_eq!(cache.get("INTERPOLATION_GREETING".as_ref()), Some("hello world".to_string()));
+	}
+
+	#[test]
+	#[cfg(feature = "envdot")]
+	fn interpolation_cycle() {
+		let first_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				INTERPOLATION_CYCLE_A=${INTERPOLATION_CYCLE_B}
+			"#
+		);
+		let second_file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				INTERPOLATION_CYCLE_B=${INTERPOLATION_CYCLE_A}
+			"#
+		);
+
+		std::env::remove_var("INTERPOLATION_CYCLE_A");
+		std::env::remove_var("INTERPOLATION_CYCLE_B");
+
+		let cache = Cache::builder()
+			.envdot(first_file.as_os_str())
+			.envdot(second_file.as_os_str())
+			.build();
+
+		let error = cache.try_get("INTERPOLATION_CYCLE_A".as_ref()).unwrap_err();
+		assert!(matches!(error, Error::Source { .. }));
+		assert!(error.to_string().contains("reference cycle detected"));
+	}
+
+	#[test]
+	#[cfg(all(feature = "envdot", feature = "envvars"))]
+	fn builder_strict() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				STRICT_GARAS: garas
+			"#
+		);
+
+		std::env::set_var("STRICT_AUTO", "auto");
+		std::env::remove_var("STRICT_GARAS");
+
+		let cache = Cache::builder()
+			.envvars()
+			.envdot(file.as_os_str())
+			.strict()
+			.build();
+
+		assert_eq!(cache.get("STRICT_AUTO".as_ref()), Some("auto".to_string()));
+		let error = cache.try_get("STRICT_GARAS".as_ref()).unwrap_err();
+		assert!(matches!(error, Error::Source { .. }));
+	}
+
+	#[test]
+	#[cfg(feature = "envdot")]
+	fn builder_lenient() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				LENIENT_GARAS: garas
+				LENIENT_AUTO=auto
+			"#
+		);
+
+		let reported = Arc::new(std::sync::Mutex::new(0));
+		let hook_reported = reported.clone();
+
+		let cache = Cache::builder()
+			.envdot(file.as_os_str())
+			.lenient(move |_| *hook_reported.lock().unwrap() += 1)
+			.build();
+
+		assert_eq!(cache.get("LENIENT_AUTO".as_ref()), Some("auto".to_string()));
+		assert_eq!(cache.get("LENIENT_GARAS".as_ref()), None);
+		assert_eq!(*reported.lock().unwrap(), 1);
+	}
+
+	#[test]
+	#[cfg(feature = "envdot")]
+	fn builder_cascade() {
+		let directory = tempfile::tempdir().unwrap();
+		let write = |name: &str, contents: &str| std::fs::write(directory.path().join(name), contents).unwrap();
+		write(".env", "CASCADE_A=env\nCASCADE_B=env\nCASCADE_C=env\nCASCADE_D=env\n");
+		write(".env.staging", "CASCADE_A=staging\nCASCADE_B=staging\nCASCADE_C=staging\n");
+		write(".env.local", "CASCADE_A=local\nCASCADE_B=local\n");
+		write(".env.staging.local", "CASCADE_A=staging.local\n");
+		write(".env.test", "CASCADE_A=test\n");
+
+		for key in ["CASCADE_A", "CASCADE_B", "CASCADE_C", "CASCADE_D"] {
+			std::env::remove_var(key);
+		}
+
+		std::env::set_var("CASCADE_PROFILE", "staging");
+		let cache = Cache::builder()
+			.profile_variable("CASCADE_PROFILE")
+			.envdot_cascade(directory.path())
+			.build();
+		assert_eq!(cache.get("CASCADE_A".as_ref()), Some("staging.local".to_string()));
+		assert_eq!(cache.get("CASCADE_B".as_ref()), Some("local".to_string()));
+		assert_eq!(cache.get("CASCADE_C".as_ref()), Some("staging".to_string()));
+		assert_eq!(cache.get("CASCADE_D".as_ref()), Some("env".to_string()));
+
+		std::env::set_var("CASCADE_PROFILE", "test");
+		let cache = Cache::builder()
+			.envdot_cascade(directory.path())
+			.profile_variable("CASCADE_PROFILE")
+			.build();
+		assert_eq!(cache.get("CASCADE_A".as_ref()), Some("test".to_string()));
+		assert_eq!(cache.get("CASCADE_B".as_ref()), Some("env".to_string()));
+	}
+
+	#[test]
+	#[cfg(feature = "envfiles")]
+	fn builder_trim() {
+		let file = tempfile_fixture("  token\n");
+		std::env::remove_var("BUILDER_TRIM");
+		std::env::set_var("BUILDER_TRIM_FILE", file.as_os_str());
+
+		let cache = Cache::builder()
+			.envfiles()
+			.build();
+		assert_eq!(cache.get("BUILDER_TRIM".as_ref()), Some("  token".to_string()));
+
+		let cache = Cache::builder()
+			.envfiles()
+			.trim(Trim::Whitespace)
+			.build();
+		assert_eq!(cache.get("BUILDER_TRIM".as_ref()), Some("token".to_string()));
+
+		let (value, _) = cache.try_lookup_trimmed("BUILDER_TRIM".as_ref(), Trim::None).unwrap().unwrap();
+		assert_eq!(value, "  token\n");
+	}
+
+	#[test]
+	#[cfg(all(feature = "envdir", feature = "envvars"))]
+	fn builder_envdir() {
+		let directory = tempfile::tempdir().unwrap();
+		std::fs::write(directory.path().join("builder-envdir"), "  envdir\n").unwrap();
+		std::env::remove_var("BUILDER_ENVDIR");
+
+		let cache = Cache::builder()
+			.envvars()
+			.envdir_mapped([directory.path()], crate::envdir::Mapping::Kebab)
+			.trim(Trim::Whitespace)
+			.build();
+		let (value, provenance) = cache.try_lookup("BUILDER_ENVDIR".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "envdir");
+		assert_eq!(provenance.source, "envdir");
+
+		std::env::set_var("BUILDER_ENVDIR", "envvars");
+		assert_eq!(cache.get("BUILDER_ENVDIR".as_ref()), Some("envvars".to_string()));
+	}
 }
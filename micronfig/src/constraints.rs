@@ -0,0 +1,61 @@
+//! **Private**; utilities used by the `where` constraints of the [`config`](crate::config) macro.
+
+/// Check whether the given value satisfies the given predicate.
+///
+/// Allows the types of the parameters of closures to be inferred.
+pub fn satisfies<T, F>(value: &T, predicate: F) -> bool
+	where F: FnOnce(&T) -> bool
+{
+	predicate(value)
+}
+
+/// A regular expression used by a pattern constraint, compiled only once, the first time it is matched against.
+///
+/// The [`config`](crate::config) macro validates the expression at compile-time, so that it never fails to compile at runtime.
+#[cfg(feature = "regex")]
+#[derive(Debug)]
+pub struct Pattern {
+	/// The source of the regular expression.
+	pattern: &'static str,
+
+	/// The compiled regular expression, if it was already used.
+	regex: std::sync::OnceLock<regex::Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+	/// Create a pattern from the given regular expression, without compiling it yet.
+	pub const fn new(pattern: &'static str) -> Self {
+		Self { pattern, regex: std::sync::OnceLock::new() }
+	}
+
+	/// Check whether the given value matches the regular expression, compiling it if it wasn't already.
+	///
+	/// # Panics
+	///
+	/// If the regular expression is invalid, which cannot happen for patterns validated by the [`config`](crate::config) macro.
+	pub fn is_match(&self, value: &str) -> bool {
+		self.regex
+			.get_or_init(|| regex::Regex::new(self.pattern).unwrap_or_else(|err| panic!("{:?} is not a valid regular expression: {err}", self.pattern)))
+			.is_match(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn satisfies_closure() {
+		assert!(satisfies(&2, |value| value % 2 == 0));
+		assert!(!satisfies(&3, |value| value % 2 == 0));
+	}
+
+	#[cfg(feature = "regex")]
+	#[test]
+	fn pattern_is_match() {
+		static PATTERN: Pattern = Pattern::new("^[a-z]+$");
+		assert!(PATTERN.is_match("garas"));
+		assert!(!PATTERN.is_match("GARAS"));
+	}
+}
@@ -0,0 +1,147 @@
+//! **Private**; utilities for fetching configuration values from systemd credentials.
+
+use std::ffi::{OsStr, OsString};
+use crate::cache::Resolver;
+use crate::envdir::EnvDir;
+use crate::source::{Provenance, Raw, Source, Trim};
+
+pub use crate::envdir::Mapping;
+
+/// The environment variable systemd sets to the directory containing the credentials of a service.
+pub const CREDENTIALS_VARIABLE: &str = "CREDENTIALS_DIRECTORY";
+
+/// [`Source`] retrieving values from the [credentials](https://systemd.io/CREDENTIALS/) passed by systemd to a service via `LoadCredential=` and `SetCredential=`, which are the contents of the files named after their keys in the directory specified by the [`CREDENTIALS_VARIABLE`] environment variable.
+///
+/// The directory is determined at every lookup, and no value is found if the environment variable is not set.
+///
+/// Credentials are looked for as specified by [`Credentials::mapping`] first, and then as specified by [`Credentials::fallback`], if any; for example, [`Mapping::Exact`] with a [`Mapping::Lowercase`] fallback finds either `DATABASE_PASSWORD` or `database_password`, preferring the former.
+///
+/// The contents are trimmed as specified by [`Credentials::trim`], unless a different [`Trim`] is requested for a specific lookup via [`Cache::try_lookup_trimmed`](crate::cache::Cache::try_lookup_trimmed).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Credentials {
+	/// How keys are mapped to credential names.
+	pub mapping: Mapping,
+
+	/// How keys are mapped to credential names if no credential is found as specified by [`Credentials::mapping`].
+	pub fallback: Option<Mapping>,
+
+	/// How the contents of the credentials are trimmed.
+	pub trim: Trim,
+}
+
+impl Credentials {
+	/// Create the source, mapping keys to credential names as specified.
+	pub fn new(mapping: Mapping) -> Self {
+		Self { mapping, fallback: None, trim: Trim::default() }
+	}
+
+	/// Also look for credentials named as specified by the given [`Mapping`], if none is found as specified by [`Credentials::mapping`].
+	pub fn with_fallback(mut self, fallback: Mapping) -> Self {
+		self.fallback = Some(fallback);
+		self
+	}
+
+	/// Trim the contents of the credentials as specified.
+	pub fn with_trim(mut self, trim: Trim) -> Self {
+		self.trim = trim;
+		self
+	}
+
+	/// The [`EnvDir`]s reading from the credentials directory with each [`Mapping`] in order, if the environment variable specifying it is set.
+	fn envdirs(&self) -> Vec<EnvDir> {
+		let directory = match std::env::var_os(CREDENTIALS_VARIABLE) {
+			Some(directory) => directory,
+			None => return Vec::new(),
+		};
+
+		std::iter::once(self.mapping)
+			.chain(self.fallback)
+			.map(|mapping| EnvDir::new([&directory]).with_mapping(mapping))
+			.collect()
+	}
+}
+
+impl Source for Credentials {
+	fn name(&self) -> String {
+		"credentials".to_string()
+	}
+
+	fn lookup_raw_with(&self, key: &OsStr, trim: Option<Trim>, _resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		for envdir in self.envdirs() {
+			if let Some((data, path)) = envdir.read(key, trim.unwrap_or(self.trim))? {
+				return Ok(Some((data, self.provenance().with_path(path))))
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn keys(&self) -> Option<Vec<OsString>> {
+		let mut keys: Vec<OsString> = Vec::new();
+
+		for envdir in self.envdirs() {
+			for key in envdir.keys().unwrap_or_default() {
+				if !keys.contains(&key) {
+					keys.push(key);
+				}
+			}
+		}
+
+		Some(keys)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cache::Cache;
+
+	// The credentials directory is process-wide, so everything depending on it is tested sequentially.
+	#[test]
+	fn it_works() {
+		std::env::remove_var(CREDENTIALS_VARIABLE);
+		let source = Credentials::default();
+		assert_eq!(source.get("CREDENTIALS_TOKEN".as_ref()).unwrap(), None);
+		assert_eq!(source.keys(), Some(Vec::new()));
+
+		let directory = tempfile::tempdir().unwrap();
+		std::fs::write(directory.path().join("CREDENTIALS_TOKEN"), "exact\n").unwrap();
+		std::fs::write(directory.path().join("credentials_password"), "lowercase").unwrap();
+		std::env::set_var(CREDENTIALS_VARIABLE, directory.path());
+
+		let (value, provenance) = source.lookup("CREDENTIALS_TOKEN".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "exact");
+		assert_eq!(provenance, Provenance::new("credentials").with_path(directory.path().join("CREDENTIALS_TOKEN")));
+		assert_eq!(source.get("CREDENTIALS_PASSWORD".as_ref()).unwrap(), None);
+
+		let source = Credentials::new(Mapping::Lowercase);
+		assert_eq!(source.get("CREDENTIALS_PASSWORD".as_ref()).unwrap(), Some("lowercase".to_string()));
+
+		std::fs::write(directory.path().join("credentials_token"), "shadowed").unwrap();
+		let source = Credentials::default().with_fallback(Mapping::Lowercase);
+		assert_eq!(source.get("CREDENTIALS_TOKEN".as_ref()).unwrap(), Some("exact".to_string()));
+		assert_eq!(source.get("CREDENTIALS_PASSWORD".as_ref()).unwrap(), Some("lowercase".to_string()));
+		let mut keys = source.keys().unwrap();
+		keys.sort();
+		assert_eq!(keys, vec![OsString::from("CREDENTIALS_PASSWORD"), OsString::from("CREDENTIALS_TOKEN"), OsString::from("credentials_password"), OsString::from("credentials_token")]);
+		std::fs::remove_file(directory.path().join("credentials_token")).unwrap();
+
+		let cache = Cache::builder()
+			.credentials_mapped(Mapping::Lowercase)
+			.trim(Trim::None)
+			.build();
+		let (value, provenance) = cache.try_lookup("CREDENTIALS_PASSWORD".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "lowercase");
+		assert_eq!(provenance.source, "credentials");
+		assert_eq!(cache.get("CREDENTIALS_TOKEN".as_ref()), None);
+
+		let cache = Cache::builder()
+			.credentials()
+			.trim(Trim::None)
+			.build();
+		assert_eq!(cache.get("CREDENTIALS_TOKEN".as_ref()), Some("exact\n".to_string()));
+
+		std::env::remove_var(CREDENTIALS_VARIABLE);
+		assert_eq!(cache.get("CREDENTIALS_TOKEN".as_ref()), None);
+	}
+}
@@ -0,0 +1,243 @@
+//! **Private**; utilities for fetching configuration values from files named after them in specific directories.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+use crate::cache::Resolver;
+use crate::source::{Provenance, Raw, Source, Trim};
+
+/// The directory Docker mounts secrets in, used by [`EnvDir::default`].
+pub const DEFAULT_DIRECTORY: &str = "/run/secrets";
+
+/// How the key of a variable is mapped to the name of the file containing its value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mapping {
+	/// The file is named exactly like the key, such as `DATABASE_PASSWORD`.
+	#[default]
+	Exact,
+
+	/// The file is named like the key in lowercase, such as `database_password`.
+	Lowercase,
+
+	/// The file is named like the key in lowercase, with underscores replaced by dashes, such as `database-password`, as usual for Kubernetes secrets.
+	Kebab,
+}
+
+impl Mapping {
+	/// The name of the file containing the value of the given key.
+	pub fn file_name(&self, key: &str) -> String {
+		match self {
+			Mapping::Exact => key.to_string(),
+			Mapping::Lowercase => key.to_lowercase(),
+			Mapping::Kebab => key.to_lowercase().replace('_', "-"),
+		}
+	}
+
+	/// The key whose value is contained in the file with the given name.
+	pub fn key(&self, file_name: &str) -> String {
+		match self {
+			Mapping::Exact => file_name.to_string(),
+			Mapping::Lowercase => file_name.to_uppercase(),
+			Mapping::Kebab => file_name.to_uppercase().replace('-', "_"),
+		}
+	}
+}
+
+/// [`Source`] retrieving values from the contents of the files named after their keys in one or more directories, such as the ones Docker and Kubernetes mount secrets and config maps in.
+///
+/// Directories are checked in order, and the first file found is used; subdirectories, and files whose names start with `.`, are ignored, so that the `..data` symlinks Kubernetes uses to update mounted volumes atomically are never read directly.
+///
+/// The contents are trimmed as specified by [`EnvDir::trim`], unless a different [`Trim`] is requested for a specific lookup via [`Cache::try_lookup_trimmed`](crate::cache::Cache::try_lookup_trimmed).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvDir {
+	/// The directories to look for files in, in order of priority.
+	pub directories: Vec<PathBuf>,
+
+	/// How keys are mapped to file names.
+	pub mapping: Mapping,
+
+	/// How the contents of the files are trimmed.
+	pub trim: Trim,
+}
+
+impl Default for EnvDir {
+	/// Look for files named exactly like the keys in [`DEFAULT_DIRECTORY`].
+	fn default() -> Self {
+		Self::new([DEFAULT_DIRECTORY])
+	}
+}
+
+impl EnvDir {
+	/// Create the source, looking for files named exactly like the keys in the given directories.
+	pub fn new<I, P>(directories: I) -> Self
+		where I: IntoIterator<Item = P>, P: Into<PathBuf>
+	{
+		Self {
+			directories: directories.into_iter().map(Into::into).collect(),
+			mapping: Mapping::default(),
+			trim: Trim::default(),
+		}
+	}
+
+	/// Map keys to file names as specified.
+	pub fn with_mapping(mut self, mapping: Mapping) -> Self {
+		self.mapping = mapping;
+		self
+	}
+
+	/// Trim the contents of the files as specified.
+	pub fn with_trim(mut self, trim: Trim) -> Self {
+		self.trim = trim;
+		self
+	}
+
+	/// The name of the file containing the value of the given key, if it is a valid file name which should not be ignored.
+	fn file_name(&self, key: &OsStr) -> Option<String> {
+		let file_name = self.mapping.file_name(key.to_str()?);
+
+		let mut components = Path::new(&file_name).components();
+		match (components.next(), components.next()) {
+			(Some(Component::Normal(_)), None) if !file_name.starts_with('.') => Some(file_name),
+			_ => None,
+		}
+	}
+
+	/// Read the contents of the file for the given key, trimmed as specified, along with the path of the file.
+	pub(crate) fn read(&self, key: &OsStr, trim: Trim) -> std::io::Result<Option<(Raw, PathBuf)>> {
+		let file_name = match self.file_name(key) {
+			Some(file_name) => file_name,
+			None => return Ok(None),
+		};
+
+		for directory in self.directories.iter() {
+			let path = directory.join(&file_name);
+
+			let data = match std::fs::read(&path) {
+				Ok(data) => data,
+				Err(err) if matches!(err.kind(), std::io::ErrorKind::NotFound | std::io::ErrorKind::IsADirectory) => continue,
+				Err(_) if path.is_dir() => continue,
+				Err(err) => return Err(std::io::Error::new(err.kind(), format!("to be able to read from file at {path:?}: {err}"))),
+			};
+
+			return Ok(Some((trim.decode(data), path)))
+		}
+
+		Ok(None)
+	}
+}
+
+impl Source for EnvDir {
+	fn name(&self) -> String {
+		"envdir".to_string()
+	}
+
+	fn lookup_raw_with(&self, key: &OsStr, trim: Option<Trim>, _resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		Ok(self.read(key, trim.unwrap_or(self.trim))?.map(|(data, path)| (data, self.provenance().with_path(path))))
+	}
+
+	fn keys(&self) -> Option<Vec<OsString>> {
+		let mut keys: Vec<OsString> = Vec::new();
+
+		for directory in self.directories.iter() {
+			let entries = match std::fs::read_dir(directory) {
+				Ok(entries) => entries,
+				Err(_) => continue,
+			};
+
+			for entry in entries.flatten() {
+				let file_name = match entry.file_name().into_string() {
+					Ok(file_name) if !file_name.starts_with('.') => file_name,
+					_ => continue,
+				};
+				if entry.path().is_dir() {
+					continue
+				}
+
+				let key = OsString::from(self.mapping.key(&file_name));
+				if !keys.contains(&key) {
+					keys.push(key);
+				}
+			}
+		}
+
+		Some(keys)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn it_works() {
+		let directory = tempfile::tempdir().unwrap();
+		std::fs::write(directory.path().join("DATABASE_PASSWORD"), "hunter2\n").unwrap();
+
+		let source = EnvDir::new([directory.path()]);
+		let (value, provenance) = source.lookup("DATABASE_PASSWORD".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "hunter2");
+		assert_eq!(provenance, Provenance::new("envdir").with_path(directory.path().join("DATABASE_PASSWORD")));
+		assert_eq!(source.get("MISSING".as_ref()).unwrap(), None);
+	}
+
+	#[test]
+	fn directories_priority() {
+		let first = tempfile::tempdir().unwrap();
+		let second = tempfile::tempdir().unwrap();
+		std::fs::write(first.path().join("GARAS"), "first").unwrap();
+		std::fs::write(second.path().join("GARAS"), "second").unwrap();
+		std::fs::write(second.path().join("AUTO"), "second").unwrap();
+
+		let source = EnvDir::new([first.path(), second.path()]);
+		assert_eq!(source.get("GARAS".as_ref()).unwrap(), Some("first".to_string()));
+		assert_eq!(source.get("AUTO".as_ref()).unwrap(), Some("second".to_string()));
+	}
+
+	#[test]
+	fn mapping() {
+		let directory = tempfile::tempdir().unwrap();
+		std::fs::write(directory.path().join("database-password"), "kebab").unwrap();
+		std::fs::write(directory.path().join("database_password"), "lowercase").unwrap();
+
+		let source = EnvDir::new([directory.path()]);
+		assert_eq!(source.get("DATABASE_PASSWORD".as_ref()).unwrap(), None);
+
+		let source = source.with_mapping(Mapping::Lowercase);
+		assert_eq!(source.get("DATABASE_PASSWORD".as_ref()).unwrap(), Some("lowercase".to_string()));
+
+		let source = source.with_mapping(Mapping::Kebab);
+		assert_eq!(source.get("DATABASE_PASSWORD".as_ref()).unwrap(), Some("kebab".to_string()));
+
+		assert_eq!(source.keys().unwrap(), vec![OsString::from("DATABASE_PASSWORD")]);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn kubernetes_layout() {
+		let directory = tempfile::tempdir().unwrap();
+		let timestamped = directory.path().join("..2024_01_01_00_00_00.000000000");
+		std::fs::create_dir(&timestamped).unwrap();
+		std::fs::write(timestamped.join("token"), "token").unwrap();
+		std::os::unix::fs::symlink(&timestamped, directory.path().join("..data")).unwrap();
+		std::os::unix::fs::symlink("..data/token", directory.path().join("token")).unwrap();
+
+		let source = EnvDir::new([directory.path()]).with_mapping(Mapping::Kebab);
+		assert_eq!(source.get("TOKEN".as_ref()).unwrap(), Some("token".to_string()));
+		assert_eq!(source.keys().unwrap(), vec![OsString::from("TOKEN")]);
+	}
+
+	#[test]
+	fn ignored_names() {
+		let directory = tempfile::tempdir().unwrap();
+		let nested = directory.path().join("nested");
+		std::fs::create_dir(&nested).unwrap();
+		std::fs::write(nested.join("GARAS"), "garas").unwrap();
+		std::fs::write(directory.path().join(".hidden"), "hidden").unwrap();
+
+		let source = EnvDir::new([directory.path()]);
+		assert_eq!(source.get("nested".as_ref()).unwrap(), None);
+		assert_eq!(source.get("nested/GARAS".as_ref()).unwrap(), None);
+		assert_eq!(source.get("../nested".as_ref()).unwrap(), None);
+		assert_eq!(source.get(".hidden".as_ref()).unwrap(), None);
+		assert_eq!(source.keys().unwrap(), Vec::<OsString>::new());
+	}
+}
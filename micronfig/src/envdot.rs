@@ -2,72 +2,578 @@
 
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
-use std::io::Read;
-use std::path::Path;
-use regex::Regex;
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::cache::Resolver;
+use crate::source::{Provenance, Raw, Source, Trim};
+
+pub mod document;
 
 /// The type of a parsed `.env` file.
 pub type DotEnv = HashMap<OsString, String>;
 
 /// Parse a `.env` file.
 ///
-/// Returns [`None`] if no such file is found.
-pub fn parse_dotenv<P>(value: P) -> Option<DotEnv>
+/// The de-facto format shared by most dotenv implementations is supported:
+///
+/// - empty lines and lines starting with `#` are ignored;
+/// - keys may be prefixed by `export`, and may be separated from the `=` by whitespace;
+/// - unquoted values end at the end of the line or at a `#` preceded by whitespace, and have surrounding whitespace trimmed;
+/// - single-quoted `'...'` and backtick-quoted `` `...` `` values are taken literally;
+/// - double-quoted `"..."` values support the `\n`, `\r`, `\t`, `\"`, `\\` and `\$` escape sequences;
+/// - quoted values may span multiple lines, and may be followed by a comment;
+/// - lines which are not valid entries are skipped, and reported as [`Diagnostic`]s.
+///
+/// Unquoted and double-quoted values may reference other variables with `$VAR`, `${VAR}` or `${VAR:-default}`, see [`EnvDot`] for details on how they are expanded; here, they can only refer to variables defined earlier in the same file or in the process environment.
+///
+/// Returns [`None`] if no such file is found, or a [`DotEnvError`] if it cannot be read or any [`Diagnostic`] is encountered while parsing it; see [`EnvDot::try_load`] for a lenient alternative.
+pub fn parse_dotenv<P>(value: P) -> Result<Option<DotEnv>, DotEnvError>
 	where P: AsRef<Path> + Debug
 {
-	let mut file = File::open(&value).ok()?;
-
-	let mut contents: String = String::new();
-	file.read_to_string(&mut contents)
-		.unwrap_or_else(|_| panic!("to be able to read {value:?}"));
-
-	let mut keys: HashMap<OsString, String> = HashMap::new();
-
-	let re = Regex::new(r#"^\s*(?:export\s)?\s*([^=]+?)\s*=\s*(.+)\s*$"#)
-		.expect("Regex to be valid");
-
-	contents.split("\n")
-		.filter_map(|line| re.captures(line))
-		.map(|capture| {
-			let key = &capture[1];
-			let value = &capture[2];
-
-			if value.starts_with('\'') && value.ends_with('\'') {
-				(
-					key.into(),
-					value
-						.strip_prefix('\'')
-						.expect("apostrophe to be prefixed to the value")
-						.strip_suffix('\'')
-						.expect("apostrophe to be suffixed to the value")
-						.to_owned()
-				)
+	let envdot = match EnvDot::try_load(value)? {
+		Some(envdot) => envdot,
+		None => return Ok(None),
+	};
+
+	if !envdot.diagnostics.is_empty() {
+		return Err(DotEnvError::Syntax { diagnostics: envdot.diagnostics })
+	}
+
+	Ok(Some(envdot.values()))
+}
+
+/// Parse the contents of a `.env` file into a list of its entries, and a list of the problems encountered while parsing it.
+///
+/// See [`parse_dotenv`] for the supported format; `path` is only used to fill [`Diagnostic::path`].
+#[cfg(test)]
+fn parse_str(contents: &str, path: &Path) -> (Vec<Entry>, Vec<Diagnostic>) {
+	parse_reader(contents.as_bytes(), path)
+		.expect("reading from a string to never fail")
+}
+
+/// Parse the contents of a `.env` file line by line as they are read from `reader`, like [`parse_str`] does.
+///
+/// Each entry is parsed only from its own lines: a line is appended to the entry being parsed only while a quoted value is left open, each line is scanned only once for the quote closing it, and parsing is retried only when one is found, so that files are parsed in linear time.
+///
+/// Lines are read ahead only while looking for a closing quote; if none is found, the entry is parsed from its first line alone, as an unquoted value, and the lines read ahead are kept in memory to parse the following entries.
+fn parse_reader<R>(mut reader: R, path: &Path) -> std::io::Result<(Vec<Entry>, Vec<Diagnostic>)>
+	where R: BufRead
+{
+	let mut entries = Vec::new();
+	let mut diagnostics = Vec::new();
+
+	let mut buffer = String::new();
+	let mut start = 0;
+	let mut line = 1;
+	let mut offset = 0;
+
+	loop {
+		if start == buffer.len() {
+			buffer.clear();
+			start = 0;
+			if reader.read_line(&mut buffer)? == 0 {
+				break
 			}
-			else if value.starts_with('"') && value.ends_with('"') {
-				(
-					key.into(),
-					value
-						.strip_prefix('"')
-						.expect("quotes to be prefixed to the value")
-						.strip_suffix('"')
-						.expect("quotes to be suffixed to the value")
-						.to_owned()
-				)
+		}
+
+		let first = line_end(&buffer, start);
+		let mut end = first;
+		let mut unquoted = false;
+
+		let (entry, len, next, mut problems) = loop {
+			let window = &buffer[start..end];
+			let mut cursor = Cursor { contents: window, path, rest: window, line, diagnostics: Vec::new() };
+			let entry = cursor.entry();
+
+			let unterminated = cursor.diagnostics.iter().find_map(|diagnostic| match diagnostic.reason {
+				Reason::UnterminatedQuote(quote) => Some(quote),
+				_ => None,
+			});
+			let quote = match (unterminated, unquoted) {
+				(Some(quote), false) => quote,
+				_ => break (entry, cursor.offset(), cursor.line, cursor.diagnostics),
+			};
+
+			loop {
+				if end == buffer.len() && reader.read_line(&mut buffer)? == 0 {
+					end = first;
+					unquoted = true;
+					break
+				}
+				let scanned = end;
+				end = line_end(&buffer, scanned);
+				if contains_quote(&buffer[scanned..end], quote) {
+					break
+				}
 			}
-			else {
-				(
-					key.into(),
-					value.to_owned()
-				)
+		};
+
+		line = next;
+		diagnostics.append(&mut problems);
+		if let Some(mut entry) = entry {
+			entry.span = offset + entry.span.start..offset + entry.span.end;
+			entries.push(entry);
+		}
+
+		start += len;
+		offset += len;
+	}
+
+	Ok((entries, diagnostics))
+}
+
+/// The byte offset of the end of the line starting at `start` in `buffer`, including its terminator.
+fn line_end(buffer: &str, start: usize) -> usize {
+	buffer[start..].find('\n')
+		.map_or(buffer.len(), |index| start + index + 1)
+}
+
+/// Whether `line` contains the given quote, ignoring the ones escaped by a `\` in the case of double quotes.
+fn contains_quote(line: &str, quote: char) -> bool {
+	let mut chars = line.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' if quote == '"' => {
+				chars.next();
+			},
+			c if c == quote => return true,
+			_ => {},
+		}
+	}
+	false
+}
+
+/// A problem encountered while parsing a `.env` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+	/// The path of the file.
+	pub path: PathBuf,
+	/// The line the problem was found at, starting from 1.
+	pub line: usize,
+	/// The column the problem was found at, in characters, starting from 1.
+	pub column: usize,
+	/// What the problem is.
+	pub reason: Reason,
+}
+
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}:{}: {}", self.path.display(), self.line, self.column, self.reason)
+	}
+}
+
+/// The kind of a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Reason {
+	/// The line has a value, but no key; the line is skipped.
+	MissingKey,
+	/// The key contains a character which is not alphanumeric, `_`, `.` or `-`; the line is skipped.
+	InvalidKey(char),
+	/// The key is not followed by `=`; the line is skipped.
+	MissingSeparator,
+	/// The value starts with the given quote, which is never closed; the value is parsed as if it was unquoted.
+	UnterminatedQuote(char),
+	/// The closing quote of the value is followed by something other than a comment; the rest of the line is ignored.
+	TrailingCharacters,
+}
+
+impl Display for Reason {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Reason::MissingKey => write!(f, "Missing key before `=`"),
+			Reason::InvalidKey(c) => write!(f, "Invalid character {c:?} in key"),
+			Reason::MissingSeparator => write!(f, "Missing `=` after key"),
+			Reason::UnterminatedQuote(quote) => write!(f, "Unterminated {quote} quote"),
+			Reason::TrailingCharacters => write!(f, "Unexpected characters after closing quote"),
+		}
+	}
+}
+
+/// An error encountered while loading a `.env` file.
+#[derive(Debug)]
+pub enum DotEnvError {
+	/// The file exists, but could not be read.
+	Read {
+		/// The path of the file.
+		path: PathBuf,
+		/// The error raised while reading.
+		source: std::io::Error,
+	},
+
+	/// The file could be read, but is malformed.
+	Syntax {
+		/// The problems encountered, in order of appearance.
+		diagnostics: Vec<Diagnostic>,
+	},
+}
+
+impl Display for DotEnvError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DotEnvError::Read { path, source } =>
+				write!(f, "Couldn't read {}: {source}", path.display()),
+			DotEnvError::Syntax { diagnostics } => {
+				write!(f, "Malformed .env file:")?;
+				for diagnostic in diagnostics.iter() {
+					write!(f, "\n- {diagnostic}")?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+impl std::error::Error for DotEnvError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			DotEnvError::Read { source, .. } => Some(source),
+			DotEnvError::Syntax { .. } => None,
+		}
+	}
+}
+
+/// A single variable defined in a `.env` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+	/// The line number the variable was defined at.
+	line: usize,
+	/// The key of the variable.
+	key: OsString,
+	/// The value of the variable, before references are expanded.
+	value: Template,
+	/// The byte range of the value in the contents of the file, including its quotes, if any.
+	span: Range<usize>,
+}
+
+/// A value possibly containing references to other variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Template(Vec<Segment>);
+
+/// Part of a [`Template`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+	/// Text to be used as-is.
+	Literal(String),
+	/// A `$VAR`, `${VAR}` or `${VAR:-default}` reference.
+	Reference {
+		key: String,
+		default: Option<Template>,
+	},
+}
+
+/// How a [`Template`] is delimited and escaped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+	/// An unquoted value, which only supports the `\$` escape.
+	Unquoted,
+	/// A double-quoted value, terminated by `"`.
+	Double,
+	/// The default of a `${VAR:-default}` reference, terminated by `}`, which only supports the `\$` and `\}` escapes.
+	Braced,
+}
+
+impl Template {
+	fn literal(value: &str) -> Self {
+		let mut this = Self::default();
+		this.push_str(value);
+		this
+	}
+
+	fn push(&mut self, c: char) {
+		match self.0.last_mut() {
+			Some(Segment::Literal(literal)) => literal.push(c),
+			_ => self.0.push(Segment::Literal(c.to_string())),
+		}
+	}
+
+	fn push_str(&mut self, value: &str) {
+		value.chars().for_each(|c| self.push(c));
+	}
+
+	/// Parse the template at the start of `text`, returning it along with the number of bytes it spans, including its terminator.
+	///
+	/// Returns [`None`] if the terminator of the given [`Mode`] is never found.
+	fn parse(text: &str, mode: Mode) -> Option<(Self, usize)> {
+		let mut this = Self::default();
+		let mut index = 0;
+
+		while let Some(c) = text[index..].chars().next() {
+			index += c.len_utf8();
+			match (c, mode) {
+				('"', Mode::Double) | ('}', Mode::Braced) => return Some((this, index)),
+				('\\', _) => {
+					let escaped = text[index..].chars().next();
+					let unescaped = match (escaped, mode) {
+						(Some('$'), _) => Some('$'),
+						(Some('}'), Mode::Braced) => Some('}'),
+						(Some('n'), Mode::Double) => Some('\n'),
+						(Some('r'), Mode::Double) => Some('\r'),
+						(Some('t'), Mode::Double) => Some('\t'),
+						(Some('"'), Mode::Double) => Some('"'),
+						(Some('\\'), Mode::Double) => Some('\\'),
+						_ => None,
+					};
+					match unescaped {
+						Some(unescaped) => {
+							this.push(unescaped);
+							index += escaped.map(char::len_utf8).unwrap_or_default();
+						},
+						None => this.push('\\'),
+					}
+				},
+				('$', _) => match Segment::parse_reference(&text[index..]) {
+					Some((reference, len)) => {
+						this.0.push(reference);
+						index += len;
+					},
+					None => this.push('$'),
+				},
+				('\r', Mode::Double) if text[index..].starts_with('\n') => {},
+				(c, _) => this.push(c),
 			}
-		})
-		.for_each(|(key, value)| {
-			keys.insert(key, value);
-		});
+		}
+
+		match mode {
+			Mode::Unquoted => Some((this, index)),
+			_ => None,
+		}
+	}
+
+	/// Expand all references, retrieving the values of the referenced variables via `resolve`.
+	fn expand(&self, resolve: &mut dyn FnMut(&OsStr) -> std::io::Result<Option<String>>) -> std::io::Result<String> {
+		let mut expanded = String::new();
 
-	Some(keys)
+		for segment in self.0.iter() {
+			match segment {
+				Segment::Literal(literal) => expanded.push_str(literal),
+				Segment::Reference { key, default } => {
+					let value = resolve(key.as_ref())?;
+					match (value, default) {
+						(Some(value), _) if !value.is_empty() => expanded.push_str(&value),
+						(_, Some(default)) => expanded.push_str(&default.expand(resolve)?),
+						(_, None) => {},
+					}
+				},
+			}
+		}
+
+		Ok(expanded)
+	}
+}
+
+impl Segment {
+	/// Parse the reference following a `$`, returning it along with the number of bytes it spans.
+	///
+	/// Returns [`None`] if the `$` is not followed by a valid reference, in which case it should be taken literally.
+	fn parse_reference(text: &str) -> Option<(Self, usize)> {
+		let braced = text.starts_with('{');
+		let start = if braced { 1 } else { 0 };
+
+		let len = text[start..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+			.unwrap_or(text.len() - start);
+		let key = &text[start..start + len];
+		if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+			return None
+		}
+		let end = start + len;
+
+		if !braced {
+			return Some((Self::Reference { key: key.to_owned(), default: None }, end))
+		}
+
+		let rest = &text[end..];
+		if rest.starts_with('}') {
+			Some((Self::Reference { key: key.to_owned(), default: None }, end + 1))
+		}
+		else if let Some(rest) = rest.strip_prefix(":-") {
+			let (default, default_len) = Template::parse(rest, Mode::Braced)?;
+			Some((Self::Reference { key: key.to_owned(), default: Some(default) }, end + 2 + default_len))
+		}
+		else {
+			None
+		}
+	}
+}
+
+/// Position of the parser in the contents of a `.env` file.
+struct Cursor<'c> {
+	/// The whole contents of the file.
+	contents: &'c str,
+	/// The path of the file.
+	path: &'c Path,
+	/// The contents which have not been parsed yet.
+	rest: &'c str,
+	/// The line number of the first character of [`Cursor::rest`].
+	line: usize,
+	/// The problems encountered so far.
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl<'c> Cursor<'c> {
+	fn peek(&self) -> Option<char> {
+		self.rest.chars().next()
+	}
+
+	/// The byte offset of the first character of [`Cursor::rest`] in [`Cursor::contents`].
+	fn offset(&self) -> usize {
+		self.contents.len() - self.rest.len()
+	}
+
+	/// The column of the first character of [`Cursor::rest`].
+	fn column(&self) -> usize {
+		let offset = self.offset();
+		let start = self.contents[..offset].rfind('\n')
+			.map(|index| index + 1)
+			.unwrap_or(0);
+		self.contents[start..offset].chars().count() + 1
+	}
+
+	/// Record a problem found at the given column of the current line.
+	fn diagnose(&mut self, column: usize, reason: Reason) {
+		self.diagnostics.push(Diagnostic { path: self.path.to_path_buf(), line: self.line, column, reason });
+	}
+
+	/// Advance by `len` bytes, returning the skipped contents.
+	fn advance(&mut self, len: usize) -> &'c str {
+		let (skipped, rest) = self.rest.split_at(len);
+		self.line += skipped.matches('\n').count();
+		self.rest = rest;
+		skipped
+	}
+
+	/// Skip spaces and tabs, returning whether any was skipped.
+	fn skip_blanks(&mut self) -> bool {
+		let len = self.rest.len() - self.rest.trim_start_matches([' ', '\t']).len();
+		self.advance(len);
+		len > 0
+	}
+
+	/// Skip everything up to and including the next line feed.
+	fn skip_line(&mut self) {
+		let len = match self.rest.find('\n') {
+			Some(index) => index + 1,
+			None => self.rest.len(),
+		};
+		self.advance(len);
+	}
+
+	/// Parse a single line, or multiple ones in case of a quoted value spanning multiple lines.
+	fn entry(&mut self) -> Option<Entry> {
+		self.skip_blanks();
+		let line = self.line;
+
+		if self.rest.is_empty() || self.rest.starts_with(['\n', '\r', '#']) {
+			self.skip_line();
+			return None
+		}
+
+		if let Some(rest) = self.rest.strip_prefix("export") {
+			if rest.starts_with([' ', '\t']) {
+				self.advance("export".len());
+				self.skip_blanks();
+			}
+		}
+
+		let column = self.column();
+		let len = self.rest.find(|c: char| c.is_whitespace() || c == '=')
+			.unwrap_or(self.rest.len());
+		let key = self.advance(len);
+
+		if key.is_empty() {
+			self.diagnose(column, Reason::MissingKey);
+			self.skip_line();
+			return None
+		}
+		if let Some((index, c)) = key.chars().enumerate().find(|(_, c)| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))) {
+			self.diagnose(column + index, Reason::InvalidKey(c));
+			self.skip_line();
+			return None
+		}
+
+		self.skip_blanks();
+		if !self.rest.starts_with('=') {
+			let column = self.column();
+			self.diagnose(column, Reason::MissingSeparator);
+			self.skip_line();
+			return None
+		}
+		self.advance(1);
+
+		let blank = self.skip_blanks();
+		let start = self.offset();
+		let (value, end) = match self.peek() {
+			Some(quote @ ('\'' | '"' | '`')) => {
+				let column = self.column();
+				match self.quoted(quote) {
+					Some(value) => {
+						let end = self.offset();
+						self.skip_blanks();
+						if !(self.rest.is_empty() || self.rest.starts_with(['\n', '\r', '#'])) {
+							let column = self.column();
+							self.diagnose(column, Reason::TrailingCharacters);
+						}
+						(value, end)
+					},
+					None => {
+						self.diagnose(column, Reason::UnterminatedQuote(quote));
+						self.unquoted(blank)
+					},
+				}
+			},
+			_ => self.unquoted(blank),
+		};
+
+		self.skip_line();
+		Some(Entry { line, key: key.into(), value, span: start..end })
+	}
+
+	/// Parse an unquoted value, up to the end of the line or the start of a comment.
+	///
+	/// `blank` tells whether the value is preceded by whitespace, in which case it can start with a comment.
+	///
+	/// Returns the parsed value along with the byte offset of its end, excluding trailing whitespace.
+	fn unquoted(&mut self, blank: bool) -> (Template, usize) {
+		let end = self.rest.find('\n').unwrap_or(self.rest.len());
+		let line = &self.rest[..end];
+
+		let mut previous_blank = blank;
+		let mut len = line.len();
+		for (index, c) in line.char_indices() {
+			if c == '#' && previous_blank {
+				len = index;
+				break
+			}
+			previous_blank = c.is_whitespace();
+		}
+
+		let value = self.advance(len).trim_end();
+		let end = self.offset() - (len - value.len());
+		let (template, _) = Template::parse(value.trim_start(), Mode::Unquoted)
+			.expect("unquoted templates to always be terminated");
+		(template, end)
+	}
+
+	/// Parse a value enclosed in the given quote, returning [`None`] without advancing if the quote is never closed.
+	fn quoted(&mut self, quote: char) -> Option<Template> {
+		let body = &self.rest[quote.len_utf8()..];
+
+		let (template, len) = match quote {
+			'"' => Template::parse(body, Mode::Double)?,
+			_ => {
+				let len = body.find(quote)?;
+				let value = body[..len].replace("\r\n", "\n");
+				(Template::literal(&value), len + quote.len_utf8())
+			},
+		};
+
+		self.advance(quote.len_utf8() + len);
+		Some(template)
+	}
 }
 
 /// Get the requested variable from a [`DotEnv`] structure.
@@ -76,6 +582,317 @@ pub fn get(dotenv: &DotEnv, key: &OsStr) -> Option<String>
 	dotenv.get(key).map(|v| v.to_owned())
 }
 
+/// [`Source`] retrieving values from a parsed `.env` file.
+///
+/// References to other variables in its values are expanded when they are retrieved, resolving them in the following order:
+///
+/// 1. against the variables defined earlier in the same file;
+/// 2. if it is registered in a [`Cache`](crate::cache::Cache), against the other sources of the cache, in order of priority;
+/// 3. against the process environment.
+///
+/// Undefined variables expand to an empty string, unless a `${VAR:-default}` is specified; references forming a cycle cause an error instead.
+#[derive(Clone, Debug)]
+pub struct EnvDot {
+	/// The path the file was parsed from.
+	pub path: PathBuf,
+
+	/// The variables defined in the file, in order of definition.
+	entries: Vec<Entry>,
+
+	/// The problems encountered while parsing the file.
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl EnvDot {
+	/// Parse the `.env` file at the given path, skipping malformed lines.
+	///
+	/// Returns [`None`] if no such file is found, or a [`DotEnvError::Read`] if it exists but cannot be read; the problems encountered while parsing it are available via [`EnvDot::diagnostics`].
+	pub fn try_load<P>(path: P) -> Result<Option<Self>, DotEnvError>
+		where P: AsRef<Path> + Debug
+	{
+		let read_error = |source: std::io::Error| DotEnvError::Read { path: path.as_ref().to_path_buf(), source };
+
+		let file = match File::open(&path) {
+			Ok(file) => file,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(read_error(err)),
+		};
+
+		Self::try_read(BufReader::new(file), path.as_ref()).map(Some)
+	}
+
+	/// Parse a `.env` file from the given reader, skipping malformed lines.
+	///
+	/// `path` is used as the [`EnvDot::path`] of the file; returns a [`DotEnvError::Read`] if the reader fails.
+	pub fn try_read<R, P>(reader: R, path: P) -> Result<Self, DotEnvError>
+		where R: BufRead, P: AsRef<Path>
+	{
+		let path = path.as_ref().to_path_buf();
+
+		match parse_reader(reader, &path) {
+			Ok((entries, diagnostics)) => Ok(Self { path, entries, diagnostics }),
+			Err(source) => Err(DotEnvError::Read { path, source }),
+		}
+	}
+
+	/// Parse the `.env` file at the given path, skipping malformed lines.
+	///
+	/// Returns [`None`] if no such file is found.
+	///
+	/// # Panics
+	///
+	/// If the file exists, but cannot be read; see [`EnvDot::try_load`] for a non-panicking alternative.
+	pub fn load<P>(path: P) -> Option<Self>
+		where P: AsRef<Path> + Debug
+	{
+		Self::try_load(path)
+			.unwrap_or_else(|err| panic!("{err}"))
+	}
+
+	/// The problems encountered while parsing the file, in order of appearance.
+	pub fn diagnostics(&self) -> &[Diagnostic] {
+		&self.diagnostics
+	}
+
+	/// The values of all variables defined in the file, with references expanded only against the file itself and the process environment.
+	pub fn values(&self) -> DotEnv {
+		self.entries.iter()
+			.enumerate()
+			.map(|(index, entry)| {
+				let value = self.expand(index, &mut |key| Ok(std::env::var(key).ok()))
+					.expect("expansion against the process environment to never fail");
+				(entry.key.clone(), value)
+			})
+			.collect()
+	}
+
+	/// The index of the entry which defines the given key, if any.
+	fn position(&self, key: &OsStr) -> Option<usize> {
+		self.entries.iter()
+			.rposition(|entry| entry.key == key)
+	}
+
+	/// Expand the value of the entry at the given index, resolving references not defined earlier in the file via `outer`.
+	fn expand(&self, index: usize, outer: &mut dyn FnMut(&OsStr) -> std::io::Result<Option<String>>) -> std::io::Result<String> {
+		self.entries[index].value.expand(&mut |key| {
+			match self.entries[..index].iter().rposition(|entry| entry.key == key) {
+				Some(earlier) => self.expand(earlier, outer).map(Some),
+				None => outer(key),
+			}
+		})
+	}
+
+	/// Retrieve the value of the given key along with its [`Provenance`], resolving references via `outer`.
+	fn lookup_expanded(&self, key: &OsStr, outer: &mut dyn FnMut(&OsStr) -> std::io::Result<Option<String>>) -> std::io::Result<Option<(String, Provenance)>> {
+		let index = match self.position(key) {
+			Some(index) => index,
+			None => return Ok(None),
+		};
+
+		let value = self.expand(index, outer)?;
+		let provenance = self.provenance().with_line(self.entries[index].line);
+		Ok(Some((value, provenance)))
+	}
+}
+
+impl Source for EnvDot {
+	fn name(&self) -> String {
+		"envdot".to_string()
+	}
+
+	fn provenance(&self) -> Provenance {
+		Provenance::new(self.name())
+			.with_path(&self.path)
+	}
+
+	fn lookup(&self, key: &OsStr) -> std::io::Result<Option<(String, Provenance)>> {
+		self.lookup_expanded(key, &mut |key| Ok(std::env::var(key).ok()))
+	}
+
+	fn lookup_raw_with(&self, key: &OsStr, _trim: Option<Trim>, resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		let value = self.lookup_expanded(key, &mut |key| {
+			let value = resolver.resolve(key, self)?;
+			Ok(value.or_else(|| std::env::var(key).ok()))
+		})?;
+		Ok(value.map(|(value, provenance)| (Raw::Utf8(value), provenance)))
+	}
+
+	fn keys(&self) -> Option<Vec<OsString>> {
+		let mut keys: Vec<OsString> = Vec::new();
+		for entry in self.entries.iter() {
+			if !keys.contains(&entry.key) {
+				keys.push(entry.key.clone());
+			}
+		}
+		Some(keys)
+	}
+}
+
+/// The environment variable the profile used by [`cascade`] is read from by default.
+pub const PROFILE_VARIABLE: &str = "APP_ENV";
+
+/// The profile in which `.env.local` is skipped by [`cascade`], so that tests are not affected by local overrides.
+pub const TEST_PROFILE: &str = "test";
+
+/// The paths of the `.env` files which should be registered for the given profile in the given directory, in order of priority:
+///
+/// 1. `.env.{profile}.local`
+/// 2. `.env.local`, unless the profile is [`TEST_PROFILE`]
+/// 3. `.env.{profile}`
+/// 4. `.env`
+///
+/// If no profile is given, only `.env.local` and `.env` are returned.
+pub fn cascade(directory: &Path, profile: Option<&str>) -> Vec<PathBuf> {
+	let mut paths = Vec::new();
+
+	if let Some(profile) = profile {
+		paths.push(directory.join(format!(".env.{profile}.local")));
+	}
+	if profile != Some(TEST_PROFILE) {
+		paths.push(directory.join(".env.local"));
+	}
+	if let Some(profile) = profile {
+		paths.push(directory.join(format!(".env.{profile}")));
+	}
+	paths.push(directory.join(".env"));
+
+	paths
+}
+
+/// Where to look for the `.env` files registered by [`cascade`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Discovery {
+	/// The current working directory.
+	#[default]
+	WorkingDirectory,
+
+	/// The current working directory, or the closest of its parents containing a `.env` file, if any.
+	///
+	/// Useful to share the same `.env` file between all members of a workspace.
+	Parents,
+
+	/// The directory containing the executable of the current process.
+	Executable,
+
+	/// The given directory; `env!("CARGO_MANIFEST_DIR")` can be used to refer to the directory of the crate at compile-time.
+	Directory(PathBuf),
+}
+
+impl Discovery {
+	/// Find the directory the `.env` files should be looked for in.
+	///
+	/// Falls back to the current working directory if the directory cannot be determined.
+	pub fn directory(&self) -> PathBuf {
+		let directory = match self {
+			Discovery::WorkingDirectory => None,
+			Discovery::Parents => std::env::current_dir().ok()
+				.and_then(|directory| find_parent(&directory)),
+			Discovery::Executable => std::env::current_exe().ok()
+				.and_then(|executable| executable.parent().map(Path::to_path_buf)),
+			Discovery::Directory(directory) => Some(directory.clone()),
+		};
+
+		directory.unwrap_or_else(|| PathBuf::from("."))
+	}
+}
+
+/// Find the closest of the given directory and its parents containing a `.env` file.
+fn find_parent(directory: &Path) -> Option<PathBuf> {
+	directory.ancestors()
+		.find(|ancestor| ancestor.join(".env").is_file())
+		.map(Path::to_path_buf)
+}
+
+/// Read the profile from the given environment variable, ignoring it if empty.
+pub fn profile(variable: &str) -> Option<String> {
+	std::env::var(variable).ok()
+		.filter(|profile| !profile.is_empty())
+}
+
+/// How a [`Cache`](crate::cache::Cache) should handle [`DotEnvError`]s encountered while loading `.env` files.
+#[derive(Clone)]
+pub enum Strictness {
+	/// Register the file anyway, but make every lookup reaching it fail with [`Error::Source`](crate::Error::Source).
+	Strict,
+
+	/// Call the given hook with the error, then register the file with its malformed lines skipped, if it could be read.
+	Lenient(Arc<dyn Fn(&DotEnvError) + Send + Sync>),
+}
+
+impl Default for Strictness {
+	/// Lenient, silently skipping the malformed lines, like files that cannot be read.
+	fn default() -> Self {
+		Self::Lenient(Arc::new(|_| {}))
+	}
+}
+
+impl Debug for Strictness {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Strictness::Strict => write!(f, "Strict"),
+			Strictness::Lenient(_) => write!(f, "Lenient(..)"),
+		}
+	}
+}
+
+impl Strictness {
+	/// Load the `.env` file at the given path, handling errors as specified.
+	///
+	/// Returns [`None`] if no such file is found, or if it couldn't be read in lenient mode.
+	pub(crate) fn load<P>(&self, path: P) -> Option<Arc<dyn Source>>
+		where P: AsRef<Path> + Debug
+	{
+		let error = match EnvDot::try_load(&path) {
+			Ok(None) => return None,
+			Ok(Some(envdot)) if envdot.diagnostics.is_empty() => return Some(Arc::new(envdot)),
+			Ok(Some(envdot)) => {
+				let error = DotEnvError::Syntax { diagnostics: envdot.diagnostics.clone() };
+				match self {
+					Strictness::Strict => {},
+					Strictness::Lenient(hook) => {
+						hook(&error);
+						return Some(Arc::new(envdot))
+					},
+				}
+				error
+			},
+			Err(error) => error,
+		};
+
+		match self {
+			Strictness::Strict => Some(Arc::new(Rejected { path: path.as_ref().to_path_buf(), error: Arc::new(error) })),
+			Strictness::Lenient(hook) => {
+				hook(&error);
+				None
+			},
+		}
+	}
+}
+
+/// [`Source`] standing in for a `.env` file rejected in [`Strictness::Strict`] mode, failing every lookup.
+#[derive(Debug)]
+struct Rejected {
+	/// The path of the rejected file.
+	path: PathBuf,
+	/// The reason the file was rejected.
+	error: Arc<DotEnvError>,
+}
+
+impl Source for Rejected {
+	fn name(&self) -> String {
+		"envdot".to_string()
+	}
+
+	fn get(&self, _key: &OsStr) -> std::io::Result<Option<String>> {
+		Err(std::io::Error::new(std::io::ErrorKind::InvalidData, self.error.to_string()))
+	}
+
+	fn provenance(&self) -> Provenance {
+		Provenance::new(self.name())
+			.with_path(&self.path)
+	}
+}
+
 //noinspection DotEnvSpaceAroundSeparatorInspection
 #[cfg(test)]
 mod tests {
@@ -93,7 +910,7 @@ mod tests {
 			"#
 		);
 
-		let parsed = parse_dotenv(file);
+		let parsed = parse_dotenv(file).unwrap();
 
 		let mut compared: HashMap<OsString, String> = HashMap::new();
 		compared.insert("GARAS".into(), "garas".into());
@@ -114,7 +931,7 @@ mod tests {
 			"#
 		);
 
-		let parsed = parse_dotenv(file);
+		let parsed = parse_dotenv(file).unwrap();
 
 		let mut compared: HashMap<OsString, String> = HashMap::new();
 		compared.insert("GARAS".into(), "garas".into());
@@ -135,7 +952,7 @@ mod tests {
 			"#
 		);
 
-		let parsed = parse_dotenv(file);
+		let parsed = parse_dotenv(file).unwrap();
 
 		let mut compared: HashMap<OsString, String> = HashMap::new();
 		compared.insert("GARAS".into(), "garas".into());
@@ -156,7 +973,7 @@ mod tests {
 			"#
 		);
 
-		let parsed = parse_dotenv(file);
+		let parsed = parse_dotenv(file).unwrap();
 
 		let mut compared: HashMap<OsString, String> = HashMap::new();
 		compared.insert("GARAS".into(), "garas".into());
@@ -165,4 +982,472 @@ mod tests {
 
 		assert_eq!(parsed, Some(compared));
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn source() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				GARAS=garas
+			"#
+		);
+
+		let source = EnvDot::load(file.as_os_str()).unwrap();
+		assert_eq!(source.get("GARAS".as_ref()).unwrap(), Some("garas".to_string()));
+		assert_eq!(source.get("AUTO".as_ref()).unwrap(), None);
+		assert_eq!(source.keys(), Some(vec!["GARAS".into()]));
+	}
+
+	#[test]
+	fn source_lookup() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				GARAS=garas
+				AUTO=auto
+				GARAS=again
+			"#
+		);
+
+		let source = EnvDot::load(file.as_os_str()).unwrap();
+		let (value, provenance) = source.lookup("AUTO".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "auto");
+		assert_eq!(provenance, Provenance::new("envdot").with_path(file.as_os_str()).with_line(3));
+
+		let (value, provenance) = source.lookup("GARAS".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "again");
+		assert_eq!(provenance.line, Some(4));
+	}
+
+	#[test]
+	fn dotenv_malformed() {
+		let file = tempfile_fixture(
+			// language=dotenv
+			r#"
+				GARAS=garas
+				AUTO: auto
+			"#
+		);
+
+		let error = parse_dotenv(&file).unwrap_err();
+		match error {
+			DotEnvError::Syntax { diagnostics } => {
+				assert_eq!(diagnostics.len(), 1);
+				assert_eq!(diagnostics[0].path, file.to_path_buf());
+				assert_eq!(diagnostics[0].line, 3);
+				assert_eq!(diagnostics[0].column, 9);
+				assert_eq!(diagnostics[0].reason, Reason::InvalidKey(':'));
+				assert_eq!(diagnostics[0].to_string(), format!("{}:3:9: Invalid character ':' in key", file.display()));
+			},
+			_ => panic!("expected a syntax error"),
+		}
+	}
+
+	#[test]
+	fn dotenv_unreadable() {
+		let error = parse_dotenv(std::env::temp_dir()).unwrap_err();
+		assert!(matches!(error, DotEnvError::Read { .. }));
+	}
+
+	#[test]
+	fn try_read() {
+		// A reader returning one byte at a time, so that every line is split across multiple reads.
+		let contents = b"GARAS=\"multi\nline\"\nAUTO='unterminated\nBUS=bus\n";
+		let reader = std::io::BufReader::with_capacity(1, &contents[..]);
+
+		let envdot = EnvDot::try_read(reader, "garas.env").unwrap();
+		assert_eq!(envdot.path, PathBuf::from("garas.env"));
+		assert_eq!(envdot.diagnostics().len(), 1);
+		assert_eq!(envdot.diagnostics()[0].line, 3);
+
+		let values = envdot.values();
+		assert_eq!(values.get(OsStr::new("GARAS")).map(String::as_str), Some("multi\nline"));
+		assert_eq!(values.get(OsStr::new("AUTO")).map(String::as_str), Some("'unterminated"));
+		assert_eq!(values.get(OsStr::new("BUS")).map(String::as_str), Some("bus"));
+	}
+
+	#[test]
+	fn try_read_invalid_utf8() {
+		let error = EnvDot::try_read(&b"GARAS=\xFF\n"[..], "garas.env").unwrap_err();
+		assert!(matches!(error, DotEnvError::Read { .. }));
+	}
+
+	#[test]
+	fn dotenv_missing() {
+		assert!(parse_dotenv("/this/file/does/not/exist").unwrap().is_none());
+	}
+
+	#[test]
+	fn strictness_strict() {
+		let file = tempfile_fixture("GARAS: garas");
+
+		let source = Strictness::Strict.load(&file).unwrap();
+		let error = source.get("GARAS".as_ref()).unwrap_err();
+		assert!(error.to_string().contains("Invalid character ':' in key"));
+	}
+
+	#[test]
+	fn strictness_lenient() {
+		let file = tempfile_fixture("GARAS: garas\nAUTO=auto");
+		let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+		let hook_reported = reported.clone();
+		let strictness = Strictness::Lenient(Arc::new(move |error| hook_reported.lock().unwrap().push(error.to_string())));
+
+		let source = strictness.load(&file).unwrap();
+		assert_eq!(source.get("AUTO".as_ref()).unwrap(), Some("auto".to_string()));
+		assert_eq!(source.get("GARAS".as_ref()).unwrap(), None);
+		assert_eq!(reported.lock().unwrap().len(), 1);
+	}
+
+	#[test]
+	fn cascade_none() {
+		assert_eq!(cascade(".".as_ref(), None), vec![PathBuf::from("./.env.local"), PathBuf::from("./.env")]);
+	}
+
+	#[test]
+	fn cascade_profile() {
+		assert_eq!(cascade(".".as_ref(), Some("production")), vec![
+			PathBuf::from("./.env.production.local"),
+			PathBuf::from("./.env.local"),
+			PathBuf::from("./.env.production"),
+			PathBuf::from("./.env"),
+		]);
+	}
+
+	#[test]
+	fn cascade_test() {
+		assert_eq!(cascade(".".as_ref(), Some("test")), vec![
+			PathBuf::from("./.env.test.local"),
+			PathBuf::from("./.env.test"),
+			PathBuf::from("./.env"),
+		]);
+	}
+
+	#[test]
+	fn discovery_parents() {
+		let root = tempfile::tempdir().unwrap();
+		let nested = root.path().join("member").join("src");
+		std::fs::create_dir_all(&nested).unwrap();
+
+		assert_eq!(find_parent(&nested), None);
+
+		std::fs::write(root.path().join(".env"), "GARAS=garas").unwrap();
+		assert_eq!(find_parent(&nested), Some(root.path().to_path_buf()));
+
+		std::fs::write(root.path().join("member").join(".env"), "GARAS=garas").unwrap();
+		assert_eq!(find_parent(&nested), Some(root.path().join("member")));
+	}
+
+	#[test]
+	fn discovery_directory() {
+		assert_eq!(Discovery::WorkingDirectory.directory(), PathBuf::from("."));
+		assert_eq!(Discovery::Directory("/etc/app".into()).directory(), PathBuf::from("/etc/app"));
+		assert_eq!(Discovery::Executable.directory(), std::env::current_exe().unwrap().parent().unwrap());
+	}
+
+	#[test]
+	fn profile_empty() {
+		std::env::set_var("PROFILE_EMPTY", "");
+		assert_eq!(profile("PROFILE_EMPTY"), None);
+		std::env::set_var("PROFILE_EMPTY", "development");
+		assert_eq!(profile("PROFILE_EMPTY"), Some("development".to_string()));
+	}
+
+	#[test]
+	fn source_missing() {
+		assert!(EnvDot::load("/this/file/does/not/exist").is_none());
+	}
+
+	/// Conformance suite for the de-facto dotenv format, see [`parse_dotenv`](super::super::parse_dotenv).
+	mod conformance {
+		use super::super::{parse_str, EnvDot, Reason};
+
+		fn parse(contents: &str) -> Vec<(String, String)> {
+			let (entries, diagnostics) = parse_str(contents, "".as_ref());
+			let envdot = EnvDot { path: Default::default(), entries, diagnostics };
+			envdot.entries.iter()
+				.enumerate()
+				.map(|(index, entry)| {
+					let value = envdot.expand(index, &mut |_| Ok(None)).unwrap();
+					(entry.key.clone().into_string().unwrap(), value)
+				})
+				.collect()
+		}
+
+		fn entry(key: &str, value: &str) -> (String, String) {
+			(key.to_string(), value.to_string())
+		}
+
+		#[test]
+		fn basic() {
+			assert_eq!(parse("BASIC=basic"), vec![entry("BASIC", "basic")]);
+		}
+
+		#[test]
+		fn empty_file() {
+			assert_eq!(parse(""), vec![]);
+		}
+
+		#[test]
+		fn empty_value() {
+			assert_eq!(parse("EMPTY=\nEMPTY_SPACED= \n"), vec![entry("EMPTY", ""), entry("EMPTY_SPACED", "")]);
+		}
+
+		#[test]
+		fn empty_quoted() {
+			assert_eq!(parse("SINGLE=''\nDOUBLE=\"\"\n"), vec![entry("SINGLE", ""), entry("DOUBLE", "")]);
+		}
+
+		#[test]
+		fn whitespace_around_separator() {
+			assert_eq!(parse("  SPACED  =  value  "), vec![entry("SPACED", "value")]);
+		}
+
+		#[test]
+		fn whitespace_inside_unquoted() {
+			assert_eq!(parse("INNER=some value"), vec![entry("INNER", "some value")]);
+		}
+
+		#[test]
+		fn whitespace_inside_quoted() {
+			assert_eq!(parse("QUOTED='  padded  '"), vec![entry("QUOTED", "  padded  ")]);
+		}
+
+		#[test]
+		fn export() {
+			assert_eq!(parse("export EXPORTED=value"), vec![entry("EXPORTED", "value")]);
+		}
+
+		#[test]
+		fn export_as_key() {
+			assert_eq!(parse("export=value\nexported=value"), vec![entry("export", "value"), entry("exported", "value")]);
+		}
+
+		#[test]
+		fn comment_line() {
+			assert_eq!(parse("# COMMENTED=value\n  # INDENTED=value\nKEY=value"), vec![entry("KEY", "value")]);
+		}
+
+		#[test]
+		fn comment_inline_unquoted() {
+			assert_eq!(parse("KEY=value # comment"), vec![entry("KEY", "value")]);
+		}
+
+		#[test]
+		fn comment_inline_empty() {
+			assert_eq!(parse("KEY= # comment"), vec![entry("KEY", "")]);
+		}
+
+		#[test]
+		fn comment_inline_quoted() {
+			assert_eq!(parse("KEY=\"value # not a comment\" # comment"), vec![entry("KEY", "value # not a comment")]);
+		}
+
+		#[test]
+		fn hash_without_whitespace() {
+			assert_eq!(parse("COLOR=#ff0000\nURL=http://example.org/#anchor"), vec![entry("COLOR", "#ff0000"), entry("URL", "http://example.org/#anchor")]);
+		}
+
+		#[test]
+		fn equals_in_value() {
+			assert_eq!(parse("EQUATION=a=b=c"), vec![entry("EQUATION", "a=b=c")]);
+		}
+
+		#[test]
+		fn single_quotes_literal() {
+			assert_eq!(parse(r#"LITERAL='a\nb "c"'"#), vec![entry("LITERAL", r#"a\nb "c""#)]);
+		}
+
+		#[test]
+		fn backticks_literal() {
+			assert_eq!(parse(r#"LITERAL=`it's "quoted"`"#), vec![entry("LITERAL", r#"it's "quoted""#)]);
+		}
+
+		#[test]
+		fn double_quotes_escapes() {
+			assert_eq!(parse(r#"ESCAPED="a\nb\tc\r\"d\" \\e""#), vec![entry("ESCAPED", "a\nb\tc\r\"d\" \\e")]);
+		}
+
+		#[test]
+		fn double_quotes_unknown_escape() {
+			assert_eq!(parse(r#"UNKNOWN="a\qb""#), vec![entry("UNKNOWN", r"a\qb")]);
+		}
+
+		#[test]
+		fn multiline_double() {
+			assert_eq!(parse("MULTI=\"first\nsecond\"\nNEXT=next"), vec![entry("MULTI", "first\nsecond"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn multiline_single() {
+			assert_eq!(parse("MULTI='first\nsecond'\nNEXT=next"), vec![entry("MULTI", "first\nsecond"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn multiline_crlf() {
+			assert_eq!(parse("MULTI=\"first\r\nsecond\"\r\nNEXT=next\r\n"), vec![entry("MULTI", "first\nsecond"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn multiline_escaped_quotes() {
+			assert_eq!(parse("MULTI=\"a \\\"b\\\"\nc \\\\\"\nNEXT=next"), vec![entry("MULTI", "a \"b\"\nc \\"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn unterminated_quote() {
+			assert_eq!(parse("OPEN=\"value\nNEXT=next"), vec![entry("OPEN", "\"value"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn unterminated_quote_before_multiline() {
+			assert_eq!(parse("OPEN='value\nMULTI=\"a\nb\"\nNEXT=next"), vec![entry("OPEN", "'value"), entry("MULTI", "a\nb"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn mismatched_quotes() {
+			assert_eq!(parse("MISMATCHED='value\""), vec![entry("MISMATCHED", "'value\"")]);
+		}
+
+		#[test]
+		fn quote_inside_unquoted() {
+			assert_eq!(parse("INNER=it's"), vec![entry("INNER", "it's")]);
+		}
+
+		#[test]
+		fn trailing_garbage_after_quote() {
+			assert_eq!(parse("KEY=\"value\" garbage\nNEXT=next"), vec![entry("KEY", "value"), entry("NEXT", "next")]);
+		}
+
+		#[test]
+		fn invalid_lines_skipped() {
+			assert_eq!(parse("NOT AN ENTRY\n=value\nKEY=value"), vec![entry("KEY", "value")]);
+		}
+
+		#[test]
+		fn unicode() {
+			assert_eq!(parse("GREETING=ciao 👋\nQUOTED=\"perché\""), vec![entry("GREETING", "ciao 👋"), entry("QUOTED", "perché")]);
+		}
+
+		#[test]
+		fn interpolation_braced() {
+			assert_eq!(parse("HOST=localhost\nURL=http://${HOST}:8080"), vec![entry("HOST", "localhost"), entry("URL", "http://localhost:8080")]);
+		}
+
+		#[test]
+		fn interpolation_bare() {
+			assert_eq!(parse("HOST=localhost\nURL=$HOST/path"), vec![entry("HOST", "localhost"), entry("URL", "localhost/path")]);
+		}
+
+		#[test]
+		fn interpolation_double_quotes() {
+			assert_eq!(parse("HOST=localhost\nURL=\"http://${HOST} \\${HOST}\""), vec![entry("HOST", "localhost"), entry("URL", "http://localhost ${HOST}")]);
+		}
+
+		#[test]
+		fn interpolation_single_quotes() {
+			assert_eq!(parse("HOST=localhost\nURL='http://${HOST}'"), vec![entry("HOST", "localhost"), entry("URL", "http://${HOST}")]);
+		}
+
+		#[test]
+		fn interpolation_escaped_unquoted() {
+			assert_eq!(parse("PRICE=\\$HOST"), vec![entry("PRICE", "$HOST")]);
+		}
+
+		#[test]
+		fn interpolation_undefined() {
+			assert_eq!(parse("URL=http://${HOST}/"), vec![entry("URL", "http:///")]);
+		}
+
+		#[test]
+		fn interpolation_later() {
+			assert_eq!(parse("URL=$HOST\nHOST=localhost"), vec![entry("URL", ""), entry("HOST", "localhost")]);
+		}
+
+		#[test]
+		fn interpolation_redefined() {
+			assert_eq!(parse("PATH=a\nPATH=${PATH}:b"), vec![entry("PATH", "a"), entry("PATH", "a:b")]);
+		}
+
+		#[test]
+		fn interpolation_chained() {
+			assert_eq!(parse("A=a\nB=${A}b\nC=${B}c"), vec![entry("A", "a"), entry("B", "ab"), entry("C", "abc")]);
+		}
+
+		#[test]
+		fn interpolation_default() {
+			assert_eq!(parse("EMPTY=\nA=${MISSING:-fallback}\nB=${EMPTY:-fallback}"), vec![entry("EMPTY", ""), entry("A", "fallback"), entry("B", "fallback")]);
+		}
+
+		#[test]
+		fn interpolation_default_nested() {
+			assert_eq!(parse("HOST=localhost\nURL=${MISSING:-http://${HOST:-unknown}/\\}}"), vec![entry("HOST", "localhost"), entry("URL", "http://localhost/}")]);
+		}
+
+		#[test]
+		fn interpolation_literal_dollar() {
+			assert_eq!(parse("PRICE=5$\nARG=$1\nOPEN=${HOST\nEMPTY=${}"), vec![entry("PRICE", "5$"), entry("ARG", "$1"), entry("OPEN", "${HOST"), entry("EMPTY", "${}")]);
+		}
+
+		fn diagnose(contents: &str) -> Vec<(usize, usize, Reason)> {
+			let (_, diagnostics) = parse_str(contents, "".as_ref());
+			diagnostics.into_iter()
+				.map(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.reason))
+				.collect()
+		}
+
+		#[test]
+		fn diagnostics_none() {
+			assert_eq!(diagnose("# comment\n\n  \nexport A=a # comment\nB='b' # comment\nC=\"c\nc\"\r\nD=\n"), vec![]);
+		}
+
+		#[test]
+		fn diagnostics_invalid_key() {
+			assert_eq!(diagnose("DATABASE_URL: postgres://localhost"), vec![(1, 13, Reason::InvalidKey(':'))]);
+		}
+
+		#[test]
+		fn diagnostics_missing_key() {
+			assert_eq!(diagnose("A=a\n  =value"), vec![(2, 3, Reason::MissingKey)]);
+		}
+
+		#[test]
+		fn diagnostics_missing_separator() {
+			assert_eq!(diagnose("KEY value\nexport"), vec![(1, 5, Reason::MissingSeparator), (2, 7, Reason::MissingSeparator)]);
+		}
+
+		#[test]
+		fn diagnostics_unterminated_quote() {
+			assert_eq!(diagnose("KEY=\"open\nNEXT='open"), vec![(1, 5, Reason::UnterminatedQuote('"')), (2, 6, Reason::UnterminatedQuote('\''))]);
+		}
+
+		#[test]
+		fn diagnostics_trailing_characters() {
+			assert_eq!(diagnose("KEY='v' x\nMULTI=\"a\nb\" c"), vec![(1, 9, Reason::TrailingCharacters), (3, 4, Reason::TrailingCharacters)]);
+		}
+
+		#[test]
+		fn diagnostics_unicode_column() {
+			assert_eq!(diagnose("PERCHÉ?=no"), vec![(1, 7, Reason::InvalidKey('?'))]);
+		}
+
+		#[test]
+		fn line_numbers() {
+			let (entries, _) = parse_str("# comment\nFIRST=1\nMULTI=\"a\nb\"\n\nLAST=2", "".as_ref());
+			let lines: Vec<usize> = entries.into_iter()
+				.map(|entry| entry.line)
+				.collect();
+			assert_eq!(lines, vec![2, 3, 6]);
+		}
+
+		#[test]
+		fn spans() {
+			let contents = "export FIRST = 'one' # comment\nMULTI=\"a\nb\"\nLAST=  last  \n";
+			let (entries, _) = parse_str(contents, "".as_ref());
+			let spans: Vec<&str> = entries.into_iter()
+				.map(|entry| &contents[entry.span])
+				.collect();
+			assert_eq!(spans, vec!["'one'", "\"a\nb\"", "last"]);
+		}
+	}
+}
@@ -0,0 +1,371 @@
+//! Lossless editing of `.env` files.
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use super::{Cursor, Reason, Template};
+
+/// A `.env` file which can be edited while preserving its comments, blank lines, ordering and quoting.
+///
+/// Parsing a file and serializing it back via [`Display`] always reproduces it byte-for-byte; only the entries changed with [`Document::set`] and [`Document::remove`] are rewritten.
+///
+/// ```
+/// use micronfig::envdot::document::Document;
+///
+/// let mut document = Document::parse("# Local overrides\nexport TOKEN=old # rotated weekly\n");
+/// document.set("TOKEN", "new token").unwrap();
+/// document.set("PORT", "8080").unwrap();
+///
+/// assert_eq!(document.to_string(), "# Local overrides\nexport TOKEN='new token' # rotated weekly\nPORT=8080\n");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+	/// The parts the file is made of, in order.
+	lines: Vec<Line>,
+}
+
+/// A part of a [`Document`], spanning one or more lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Line {
+	/// Text which does not define a variable, such as blank lines, comments and malformed lines, kept as-is.
+	Other(String),
+
+	/// The definition of a variable.
+	Entry {
+		/// The key of the variable.
+		key: String,
+		/// The text preceding the value, including the key and the `=`.
+		before: String,
+		/// The value as written in the file, including its quotes, if any.
+		value: String,
+		/// The text following the value, including the comment and the line terminator, if any.
+		after: String,
+		/// The parsed value, before references are expanded.
+		template: Template,
+	},
+}
+
+impl Document {
+	/// Parse the contents of a `.env` file.
+	///
+	/// Malformed lines are kept as-is, and do not define any variable.
+	pub fn parse(contents: &str) -> Self {
+		let mut cursor = Cursor { contents, path: Path::new(""), rest: contents, line: 1, diagnostics: Vec::new() };
+		let mut lines = Vec::new();
+
+		while !cursor.rest.is_empty() {
+			let start = cursor.offset();
+			let entry = cursor.entry();
+			let end = cursor.offset();
+
+			lines.push(match entry {
+				Some(entry) => Line::Entry {
+					key: entry.key.to_string_lossy().into_owned(),
+					before: contents[start..entry.span.start].to_string(),
+					value: contents[entry.span.clone()].to_string(),
+					after: contents[entry.span.end..end].to_string(),
+					template: entry.value,
+				},
+				None => Line::Other(contents[start..end].to_string()),
+			});
+		}
+
+		Self { lines }
+	}
+
+	/// Read and parse the `.env` file at the given path.
+	pub fn load<P>(path: P) -> std::io::Result<Self>
+		where P: AsRef<Path>
+	{
+		let contents = std::fs::read_to_string(path)?;
+		Ok(Self::parse(&contents))
+	}
+
+	/// Serialize the document, and write it to the given path.
+	pub fn save<P>(&self, path: P) -> std::io::Result<()>
+		where P: AsRef<Path>
+	{
+		std::fs::write(path, self.to_string())
+	}
+
+	/// The keys of all variables defined in the document, in order of first definition.
+	pub fn keys(&self) -> Vec<&str> {
+		let mut keys: Vec<&str> = Vec::new();
+		for line in self.lines.iter() {
+			if let Line::Entry { key, .. } = line {
+				if !keys.contains(&key.as_str()) {
+					keys.push(key);
+				}
+			}
+		}
+		keys
+	}
+
+	/// Whether the given variable is defined in the document.
+	pub fn contains_key(&self, key: &str) -> bool {
+		self.position(key).is_some()
+	}
+
+	/// The value of the given variable as written in the document, including its quotes, if any.
+	pub fn get_raw(&self, key: &str) -> Option<&str> {
+		match &self.lines[self.position(key)?] {
+			Line::Entry { value, .. } => Some(value),
+			Line::Other(_) => unreachable!("position to only return entries"),
+		}
+	}
+
+	/// The value of the given variable, with references expanded only against the variables defined earlier in the document.
+	pub fn get(&self, key: &str) -> Option<String> {
+		let index = self.position(key)?;
+		Some(self.expand(index))
+	}
+
+	/// Set the value of the given variable, quoting and escaping it as needed.
+	///
+	/// If the variable is already defined, the value of its last definition is replaced, preserving the rest of the line; otherwise, a new definition is appended at the end of the document.
+	///
+	/// Returns [`Reason::MissingKey`] or [`Reason::InvalidKey`] if the key could not be parsed back.
+	pub fn set(&mut self, key: &str, value: &str) -> Result<(), Reason> {
+		validate_key(key)?;
+		let serialized = serialize(value);
+
+		match self.position(key) {
+			Some(index) => {
+				if let Line::Entry { before, value: line_value, after, template, .. } = &mut self.lines[index] {
+					let preceding = match serialized.is_empty() {
+						true => before.as_str(),
+						false => serialized.as_str(),
+					};
+					if after.starts_with('#') && !preceding.ends_with(char::is_whitespace) {
+						after.insert(0, ' ');
+					}
+					*line_value = serialized;
+					*template = Template::literal(value);
+				}
+			},
+			None => {
+				if !self.to_string().ends_with('\n') && !self.lines.is_empty() {
+					self.lines.push(Line::Other("\n".to_string()));
+				}
+				self.lines.push(Line::Entry {
+					key: key.to_string(),
+					before: format!("{key}="),
+					value: serialized,
+					after: "\n".to_string(),
+					template: Template::literal(value),
+				});
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Remove all definitions of the given variable, returning whether any was found.
+	pub fn remove(&mut self, key: &str) -> bool {
+		let before = self.lines.len();
+		self.lines.retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+		self.lines.len() != before
+	}
+
+	/// The index of the last definition of the given key, if any.
+	fn position(&self, key: &str) -> Option<usize> {
+		position(&self.lines, key)
+	}
+
+	/// Expand the value of the entry at the given index against the entries preceding it.
+	fn expand(&self, index: usize) -> String {
+		expand(&self.lines, index)
+			.expect("expansion without outer variables to never fail")
+	}
+}
+
+/// The index of the last definition of the given key among `lines`, if any.
+fn position(lines: &[Line], key: &str) -> Option<usize> {
+	lines.iter()
+		.rposition(|line| matches!(line, Line::Entry { key: k, .. } if k == key))
+}
+
+/// Expand the value of the entry at the given index, resolving references only against the entries of `lines` preceding it.
+fn expand(lines: &[Line], index: usize) -> std::io::Result<String> {
+	let template = match &lines[index] {
+		Line::Entry { template, .. } => template,
+		Line::Other(_) => unreachable!("position to only return entries"),
+	};
+
+	let earlier = &lines[..index];
+	template.expand(&mut |key| {
+		match key.to_str().and_then(|key| position(earlier, key)) {
+			Some(index) => expand(earlier, index).map(Some),
+			None => Ok(None),
+		}
+	})
+}
+
+impl Display for Document {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for line in self.lines.iter() {
+			match line {
+				Line::Other(text) => write!(f, "{text}")?,
+				Line::Entry { before, value, after, .. } => write!(f, "{before}{value}{after}")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Check that the given key would be parsed as such.
+fn validate_key(key: &str) -> Result<(), Reason> {
+	if key.is_empty() {
+		return Err(Reason::MissingKey)
+	}
+	match key.chars().find(|c| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))) {
+		Some(c) => Err(Reason::InvalidKey(c)),
+		None => Ok(()),
+	}
+}
+
+/// Write the given value so that it is parsed back unchanged:
+///
+/// - values made only of alphanumeric characters and `_-./:@,+%=` are left unquoted;
+/// - values without `'` and carriage returns are single-quoted, so that they are taken literally;
+/// - other values are double-quoted, escaping `\`, `"`, `$`, line feeds, carriage returns and tabs.
+fn serialize(value: &str) -> String {
+	if value.chars().all(|c| c.is_alphanumeric() || "_-./:@,+%=".contains(c)) {
+		return value.to_string()
+	}
+
+	if !value.contains(['\'', '\r']) {
+		return format!("'{value}'")
+	}
+
+	let mut serialized = String::from("\"");
+	for c in value.chars() {
+		match c {
+			'\\' => serialized.push_str("\\\\"),
+			'"' => serialized.push_str("\\\""),
+			'$' => serialized.push_str("\\$"),
+			'\n' => serialized.push_str("\\n"),
+			'\r' => serialized.push_str("\\r"),
+			'\t' => serialized.push_str("\\t"),
+			c => serialized.push(c),
+		}
+	}
+	serialized.push('"');
+	serialized
+}
+
+//noinspection DotEnvSpaceAroundSeparatorInspection
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FIXTURE: &str = concat!(
+		"# Database\r\n",
+		"export DATABASE_URI = \"postgres://localhost\" # local\r\n",
+		"\n",
+		"MULTILINE='first\nsecond'\n",
+		"MALFORMED: value\n",
+		"\tINDENTED=  spaced value   # comment\n",
+		"EMPTY=\n",
+		"TOKEN=${DATABASE_URI}/token",
+	);
+
+	#[test]
+	fn round_trip() {
+		assert_eq!(Document::parse(FIXTURE).to_string(), FIXTURE);
+		assert_eq!(Document::parse("").to_string(), "");
+	}
+
+	#[test]
+	fn keys() {
+		let document = Document::parse(FIXTURE);
+		assert_eq!(document.keys(), vec!["DATABASE_URI", "MULTILINE", "INDENTED", "EMPTY", "TOKEN"]);
+		assert!(!document.contains_key("MALFORMED"));
+	}
+
+	#[test]
+	fn get() {
+		let document = Document::parse(FIXTURE);
+		assert_eq!(document.get_raw("DATABASE_URI"), Some("\"postgres://localhost\""));
+		assert_eq!(document.get_raw("INDENTED"), Some("spaced value"));
+		assert_eq!(document.get_raw("EMPTY"), Some(""));
+		assert_eq!(document.get("MULTILINE"), Some("first\nsecond".to_string()));
+		assert_eq!(document.get("TOKEN"), Some("postgres://localhost/token".to_string()));
+		assert_eq!(document.get("MISSING"), None);
+	}
+
+	#[test]
+	fn set_existing() {
+		let mut document = Document::parse(FIXTURE);
+		document.set("DATABASE_URI", "sqlite://").unwrap();
+		document.set("INDENTED", "it's").unwrap();
+		document.set("EMPTY", "filled").unwrap();
+
+		let expected = FIXTURE
+			.replace("\"postgres://localhost\"", "sqlite://")
+			.replace("spaced value", "\"it's\"")
+			.replace("EMPTY=\n", "EMPTY=filled\n");
+		assert_eq!(document.to_string(), expected);
+		assert_eq!(document.get("DATABASE_URI"), Some("sqlite://".to_string()));
+		assert_eq!(document.get("INDENTED"), Some("it's".to_string()));
+		assert_eq!(document.get("EMPTY"), Some("filled".to_string()));
+	}
+
+	#[test]
+	fn set_last_definition() {
+		let mut document = Document::parse("GARAS=first\nGARAS=second\n");
+		document.set("GARAS", "third").unwrap();
+		assert_eq!(document.to_string(), "GARAS=first\nGARAS=third\n");
+	}
+
+	#[test]
+	fn set_before_comment() {
+		let mut document = Document::parse("GARAS= # comment\n");
+		document.set("GARAS", "garas").unwrap();
+		assert_eq!(document.to_string(), "GARAS= garas # comment\n");
+		assert_eq!(document.get("GARAS"), Some("garas".to_string()));
+	}
+
+	#[test]
+	fn set_new() {
+		let mut document = Document::parse("GARAS=garas");
+		document.set("AUTO", "auto").unwrap();
+		assert_eq!(document.to_string(), "GARAS=garas\nAUTO=auto\n");
+
+		let mut document = Document::default();
+		document.set("AUTO", "auto").unwrap();
+		assert_eq!(document.to_string(), "AUTO=auto\n");
+	}
+
+	#[test]
+	fn set_invalid_key() {
+		let mut document = Document::default();
+		assert_eq!(document.set("", "garas"), Err(Reason::MissingKey));
+		assert_eq!(document.set("GA RAS", "garas"), Err(Reason::InvalidKey(' ')));
+		assert_eq!(document, Document::default());
+	}
+
+	#[test]
+	fn remove() {
+		let mut document = Document::parse("GARAS=first\n# comment\nAUTO=auto\nGARAS=second\n");
+		assert!(document.remove("GARAS"));
+		assert!(!document.remove("GARAS"));
+		assert_eq!(document.to_string(), "# comment\nAUTO=auto\n");
+	}
+
+	#[test]
+	fn serialize_round_trip() {
+		let values = [
+			"", "plain", "with space", "#hash", "it's", "\"quoted\"", "$HOME", "${HOME}", "back\\slash",
+			"multi\nline", "carriage\r\nreturn", "tab\there", "it's $HOME\n", "ünïcödé", " padded ",
+		];
+
+		for value in values {
+			for original in ["", "VALUE=old\n", "VALUE='x'#comment\n"] {
+				let mut document = Document::parse(original);
+				document.set("VALUE", value).unwrap();
+				assert_eq!(document.get("VALUE").as_deref(), Some(value), "{:?} was set in {:?} as {document}", value, original);
+				assert_eq!(Document::parse(&document.to_string()).get("VALUE").as_deref(), Some(value), "{:?} was serialized in {:?} as {document}", value, original);
+			}
+		}
+	}
+}
@@ -2,24 +2,70 @@
 
 use std::ffi::OsStr;
 use std::io::Read;
+use std::path::PathBuf;
+use crate::cache::Resolver;
+use crate::source::{Provenance, Raw, Source, Trim};
 
-/// Get the contents of the file at the path specified by the requested environment variable plus `_FILE`.
+/// Get the contents of the file at the path specified by the requested environment variable plus `_FILE`, trimmed as specified by [`Trim::default`].
+///
+/// # Panics
+///
+/// If the file cannot be opened or read; see [`try_get`] for a non-panicking alternative.
 pub fn get(key: &OsStr) -> Option<String> {
+	try_get(key)
+		.unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Get the contents of the file at the path specified by the requested environment variable plus `_FILE`, trimmed as specified by [`Trim::default`], returning an [`std::io::Error`] if the file cannot be opened or read.
+pub fn try_get(key: &OsStr) -> std::io::Result<Option<String>> {
+	try_get_with_path(key, Trim::default())?
+		.map(|(data, _)| data.into_string())
+		.transpose()
+}
+
+/// Like [`try_get`], but trimming the contents as specified by `trim`, not requiring them to be valid UTF-8, and also returning the path of the file the contents were read from.
+fn try_get_with_path(key: &OsStr, trim: Trim) -> std::io::Result<Option<(Raw, PathBuf)>> {
 	let mut key: std::ffi::OsString = key.to_os_string();
 	key.push("_FILE");
-	let path = std::env::var(key).ok()?;
-
-	let path = std::ffi::OsString::from(path);
-	let path = std::path::PathBuf::from(path);
+	let path = match std::env::var_os(key) {
+		Some(path) => PathBuf::from(path),
+		None => return Ok(None),
+	};
 
 	let mut file = std::fs::File::open(&path)
-		.unwrap_or_else(|_| panic!("to be able to open file at {path:?}"));
+		.map_err(|err| std::io::Error::new(err.kind(), format!("to be able to open file at {path:?}: {err}")))?;
 
-	let mut data = String::new();
-	file.read_to_string(&mut data)
-		.unwrap_or_else(|_| panic!("to be able to read from file at {path:?}"));
+	let mut data = Vec::new();
+	file.read_to_end(&mut data)
+		.map_err(|err| std::io::Error::new(err.kind(), format!("to be able to read from file at {path:?}: {err}")))?;
 
-	Some(data)
+	Ok(Some((trim.decode(data), path)))
+}
+
+/// [`Source`] retrieving values from the contents of the files at the paths specified by environment variables suffixed with `_FILE`.
+///
+/// The contents are trimmed as specified by [`EnvFiles::trim`], unless a different [`Trim`] is requested for a specific lookup via [`Cache::try_lookup_trimmed`](crate::cache::Cache::try_lookup_trimmed).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvFiles {
+	/// How the contents of the files are trimmed.
+	pub trim: Trim,
+}
+
+impl EnvFiles {
+	/// Create the source, trimming the contents of the files as specified.
+	pub fn new(trim: Trim) -> Self {
+		Self { trim }
+	}
+}
+
+impl Source for EnvFiles {
+	fn name(&self) -> String {
+		"envfiles".to_string()
+	}
+
+	fn lookup_raw_with(&self, key: &OsStr, trim: Option<Trim>, _resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		Ok(try_get_with_path(key, trim.unwrap_or(self.trim))?.map(|(data, path)| (data, self.provenance().with_path(path))))
+	}
 }
 
 #[cfg(test)]
@@ -50,4 +96,47 @@ pub(crate) mod tests {
 		let value = get("NONEXISTENT".as_ref());
 		println!("{:?}", value);
 	}
+
+	#[test]
+	fn missing_file_try() {
+		std::env::set_var("NONEXISTENT_TRY_FILE", "/this/file/does/not/exist");
+		let value = try_get("NONEXISTENT_TRY".as_ref());
+		assert!(value.is_err());
+	}
+
+	#[test]
+	fn source_lookup() {
+		let file = tempfile_fixture("XYZ");
+		std::env::set_var("SOURCE_LETTERS_FILE", file.as_os_str());
+
+		let (value, provenance) = EnvFiles::default().lookup("SOURCE_LETTERS".as_ref()).unwrap().unwrap();
+		assert_eq!(value, "XYZ");
+		assert_eq!(provenance, Provenance::new("envfiles").with_path(file.as_os_str()));
+	}
+
+	#[test]
+	fn trailing_newline() {
+		let file = tempfile_fixture("token\n");
+		std::env::set_var("NEWLINE_TOKEN_FILE", file.as_os_str());
+
+		assert_eq!(get("NEWLINE_TOKEN".as_ref()), Some("token".to_string()));
+		assert_eq!(EnvFiles::new(Trim::None).get("NEWLINE_TOKEN".as_ref()).unwrap(), Some("token\n".to_string()));
+	}
+
+	#[test]
+	fn binary() {
+		let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+		std::fs::write(&path, b"\x00\xFF\n").unwrap();
+		std::env::set_var("BINARY_FILE", path.as_os_str());
+
+		assert_eq!(try_get("BINARY".as_ref()).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+		let cache = crate::cache::Cache::builder().envfiles().trim(Trim::None).build();
+		let (value, _) = cache.try_lookup_raw("BINARY".as_ref()).unwrap().unwrap();
+		assert_eq!(value, Raw::Bytes(b"\x00\xFF\n".to_vec()));
+
+		let cache = crate::cache::Cache::builder().envfiles().build();
+		let (value, _) = cache.try_lookup_as::<Vec<u8>>("BINARY".as_ref(), None).unwrap().unwrap();
+		assert_eq!(value, b"\x00\xFF\n");
+	}
 }
@@ -1,12 +1,36 @@
 //! **Private**; utilities for fetching configuration values from environment variables.
 
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
+use crate::cache::Resolver;
+use crate::source::{Provenance, Raw, Source, Trim};
 
 /// Get the specified environment variable.
+///
+/// Values which are not valid Unicode are treated as missing; see [`EnvVars`] for a source reporting them as errors instead.
 pub fn get(key: &OsStr) -> Option<String> {
 	std::env::var(key).ok()
 }
 
+/// [`Source`] retrieving values from environment variables.
+///
+/// Values which are not valid Unicode can be retrieved via [`Source::lookup_raw_with`], and cause an [`std::io::Error`] when retrieved as [`String`]s.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvVars;
+
+impl Source for EnvVars {
+	fn name(&self) -> String {
+		"envvars".to_string()
+	}
+
+	fn lookup_raw_with(&self, key: &OsStr, _trim: Option<Trim>, _resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		Ok(std::env::var_os(key).map(|value| (Raw::Os(value), self.provenance())))
+	}
+
+	fn keys(&self) -> Option<Vec<OsString>> {
+		Some(std::env::vars_os().map(|(key, _)| key).collect())
+	}
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
@@ -24,4 +48,26 @@ pub(crate) mod tests {
 		let value = get("THIS_ENVVAR_DOES_NOT_EXIST".as_ref());
 		assert_eq!(value, None);
 	}
+
+	#[test]
+	fn source() {
+		std::env::set_var("SOURCE_LETTERS", "XYZ");
+		let value = EnvVars.get("SOURCE_LETTERS".as_ref()).unwrap();
+		assert_eq!(value, Some("XYZ".to_string()));
+		assert!(EnvVars.keys().unwrap().contains(&"SOURCE_LETTERS".into()));
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn source_non_unicode() {
+		use std::os::unix::ffi::OsStrExt;
+
+		std::env::set_var("SOURCE_NON_UNICODE", OsStr::from_bytes(b"garas\xFF"));
+		assert_eq!(get("SOURCE_NON_UNICODE".as_ref()), None);
+		assert_eq!(EnvVars.get("SOURCE_NON_UNICODE".as_ref()).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+		let cache = crate::cache::Cache::builder().envvars().build();
+		let (value, _) = cache.try_lookup_raw("SOURCE_NON_UNICODE".as_ref()).unwrap().unwrap();
+		assert_eq!(value, Raw::Os(OsStr::from_bytes(b"garas\xFF").to_os_string()));
+	}
 }
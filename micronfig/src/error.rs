@@ -0,0 +1,300 @@
+//! **Private**; definition of [`Error`] and [`Report`].
+
+use std::fmt::{Debug, Display, Formatter};
+use crate::source::Provenance;
+
+/// An error encountered while retrieving the value of a configuration variable.
+///
+/// Returned by the `try_` functions generated by the [`config`](crate::config) macro; the other functions [panic] with its [`Display`] representation instead.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+	/// The variable is required, but has no value set.
+	Missing {
+		/// The key of the variable.
+		key: String,
+		/// The provenances of the sources which were checked for a value.
+		checked: Vec<Provenance>,
+	},
+
+	/// One of the sources was unable to read the value of the variable.
+	Source {
+		/// The key of the variable.
+		key: String,
+		/// The provenance of the source which failed.
+		provenance: Box<Provenance>,
+		/// The error raised while reading.
+		source: std::io::Error,
+	},
+
+	/// One of the conversions of the chain failed.
+	Conversion {
+		/// The key of the variable.
+		key: String,
+		/// The symbol of the failed conversion, such as `=>` or `>`.
+		conversion: &'static str,
+		/// The name of the type the value was being converted to.
+		target: &'static str,
+		/// The error returned by the conversion.
+		source: ConversionError,
+		/// The provenance of the value which failed to be converted.
+		provenance: Option<Box<Provenance>>,
+	},
+
+	/// The value does not satisfy one of the `where` constraints of the variable.
+	Constraint {
+		/// The key of the variable.
+		key: String,
+		/// The string representation of the constraint.
+		constraint: &'static str,
+		/// The provenance of the value which failed to satisfy the constraint.
+		provenance: Option<Box<Provenance>>,
+	},
+}
+
+impl Error {
+	/// The key of the variable the error refers to.
+	pub fn key(&self) -> &str {
+		match self {
+			Error::Missing { key, .. } => key,
+			Error::Source { key, .. } => key,
+			Error::Conversion { key, .. } => key,
+			Error::Constraint { key, .. } => key,
+		}
+	}
+}
+
+impl Error {
+	/// The provenance of the value the error refers to, if any.
+	pub fn provenance(&self) -> Option<&Provenance> {
+		match self {
+			Error::Missing { .. } => None,
+			Error::Source { provenance, .. } => Some(provenance),
+			Error::Conversion { provenance, .. } => provenance.as_deref(),
+			Error::Constraint { provenance, .. } => provenance.as_deref(),
+		}
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.key())?;
+		if let Some(provenance) = self.provenance() {
+			write!(f, " [{provenance}]")?;
+		}
+
+		match self {
+			Error::Missing { checked, .. } => {
+				write!(f, ": Is required, but has no value set")?;
+				for (index, provenance) in checked.iter().enumerate() {
+					match index {
+						0 => write!(f, " in any of: {provenance}")?,
+						_ => write!(f, ", {provenance}")?,
+					}
+				}
+				Ok(())
+			},
+			Error::Source { source, .. } =>
+				write!(f, ": Couldn't read value: {source}"),
+			Error::Conversion { conversion, target, source, .. } =>
+				write!(f, ": Couldn't perform `{conversion} {target:?}` conversion: {source}"),
+			Error::Constraint { constraint, .. } =>
+				write!(f, ": Doesn't satisfy `where {constraint}` constraint"),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Source { source, .. } => Some(source),
+			Error::Conversion { source: ConversionError::Error(source), .. } => Some(&**source),
+			_ => None,
+		}
+	}
+}
+
+/// The error returned by a failed conversion of the chain, see [`Error::Conversion`].
+#[derive(Debug)]
+pub enum ConversionError {
+	/// An error implementing [`std::error::Error`], which is exposed as the [`std::error::Error::source`] of the [`Error`].
+	Error(Box<dyn std::error::Error + Send + Sync>),
+
+	/// Any other error, which can only be displayed via its [`Debug`] representation.
+	Debug(Box<dyn Debug + Send + Sync>),
+}
+
+impl Display for ConversionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ConversionError::Error(error) => write!(f, "{error}"),
+			ConversionError::Debug(error) => write!(f, "{error:#?}"),
+		}
+	}
+}
+
+/// **Private**; wrapper used by the [`config`](crate::config) macro to select the [`ConversionError`] variant of a conversion error depending on the traits it implements.
+///
+/// `(&mut &mut Autoref(Some(error))).conversion_error()` resolves to [`FromError`] if the error implements [`std::error::Error`], and to [`FromDebug`] otherwise.
+#[doc(hidden)]
+pub struct Autoref<T>(pub Option<T>);
+
+/// **Private**; see [`Autoref`].
+#[doc(hidden)]
+pub trait FromError {
+	fn conversion_error(&mut self) -> ConversionError;
+}
+
+impl<T> FromError for &mut Autoref<T>
+	where T: std::error::Error + Send + Sync + 'static
+{
+	fn conversion_error(&mut self) -> ConversionError {
+		ConversionError::Error(Box::new(self.0.take().expect("conversion error to be taken only once")))
+	}
+}
+
+/// **Private**; see [`Autoref`].
+#[doc(hidden)]
+pub trait FromDebug {
+	fn conversion_error(&mut self) -> ConversionError;
+}
+
+impl<T> FromDebug for Autoref<T>
+	where T: Debug + Send + Sync + 'static
+{
+	fn conversion_error(&mut self) -> ConversionError {
+		ConversionError::Debug(Box::new(self.0.take().expect("conversion error to be taken only once")))
+	}
+}
+
+/// A collection of every [`Error`] encountered while retrieving all variables of a [`config`](crate::config) block.
+///
+/// Returned by the `validate` function generated by the macro.
+#[derive(Debug, Default)]
+pub struct Report {
+	/// The errors encountered, in the order the variables were defined in.
+	pub errors: Vec<&'static Error>,
+}
+
+impl Report {
+	/// Convert the report into a [`Result`], which is [`Ok`] only if no errors were encountered.
+	pub fn into_result(self) -> Result<(), Self> {
+		match self.errors.is_empty() {
+			true => Ok(()),
+			false => Err(self),
+		}
+	}
+}
+
+impl Display for Report {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} configuration variable(s) could not be retrieved:", self.errors.len())?;
+		for error in self.errors.iter() {
+			write!(f, "\n- {error}")?;
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for Report {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_missing() {
+		let error = Error::Missing { key: "GARAS".to_string(), checked: vec![] };
+		assert_eq!(error.to_string(), "GARAS: Is required, but has no value set");
+	}
+
+	#[test]
+	fn display_missing_checked() {
+		let error = Error::Missing {
+			key: "GARAS".to_string(),
+			checked: vec![
+				Provenance::new("envvars"),
+				Provenance::new("envdot").with_path("./.env"),
+			],
+		};
+		assert_eq!(error.to_string(), "GARAS: Is required, but has no value set in any of: envvars, envdot (./.env)");
+	}
+
+	#[test]
+	fn display_conversion() {
+		let error = Error::Conversion {
+			key: "GARAS".to_string(),
+			conversion: ">",
+			target: "u64",
+			source: ConversionError::Debug(Box::new(())),
+			provenance: None,
+		};
+		assert_eq!(error.to_string(), "GARAS: Couldn't perform `> \"u64\"` conversion: ()");
+	}
+
+	#[test]
+	fn display_conversion_provenance() {
+		let error = Error::Conversion {
+			key: "GARAS".to_string(),
+			conversion: ">",
+			target: "u64",
+			source: ConversionError::Debug(Box::new(())),
+			provenance: Some(Box::new(Provenance::new("envdot").with_path("./.env").with_line(3))),
+		};
+		assert_eq!(error.to_string(), "GARAS [envdot (./.env:3)]: Couldn't perform `> \"u64\"` conversion: ()");
+	}
+
+	#[test]
+	// The extra borrows are what makes the resolution fall back to `FromDebug`.
+	#[allow(clippy::needless_borrow)]
+	fn conversion_source() {
+		use std::error::Error as _;
+
+		let parse_error = "garas".parse::<u64>().unwrap_err();
+		let error = Error::Conversion {
+			key: "GARAS".to_string(),
+			conversion: ">",
+			target: "u64",
+			source: (&mut &mut Autoref(Some(parse_error.clone()))).conversion_error(),
+			provenance: None,
+		};
+		assert_eq!(error.to_string(), format!("GARAS: Couldn't perform `> \"u64\"` conversion: {parse_error}"));
+		assert_eq!(error.source().unwrap().to_string(), parse_error.to_string());
+
+		let error = Error::Conversion {
+			key: "GARAS".to_string(),
+			conversion: ">",
+			target: "()",
+			source: (&mut &mut Autoref(Some(()))).conversion_error(),
+			provenance: None,
+		};
+		assert!(matches!(error, Error::Conversion { source: ConversionError::Debug(_), .. }));
+		assert!(error.source().is_none());
+	}
+
+	#[test]
+	fn display_constraint() {
+		let error = Error::Constraint {
+			key: "GARAS".to_string(),
+			constraint: "1024 ..= 65535",
+			provenance: None,
+		};
+		assert_eq!(error.to_string(), "GARAS: Doesn't satisfy `where 1024 ..= 65535` constraint");
+	}
+
+	#[test]
+	fn report_empty() {
+		let report = Report::default();
+		assert!(report.into_result().is_ok());
+	}
+
+	#[test]
+	fn report_display() {
+		static GARAS: Error = Error::Missing { key: String::new(), checked: Vec::new() };
+		static AUTO: Error = Error::Missing { key: String::new(), checked: Vec::new() };
+
+		let report = Report { errors: vec![&GARAS, &AUTO] };
+		assert_eq!(report.to_string(), "2 configuration variable(s) could not be retrieved:\n- : Is required, but has no value set\n- : Is required, but has no value set");
+		assert!(report.into_result().is_err());
+	}
+}
@@ -71,6 +71,112 @@
 //! }
 //! ```
 //!
+//! ### Renaming variables
+//!
+//! By default, the name of the function is also the key used to look the value up in the sources.
+//!
+//! To use a different key, for example to give the function an idiomatic snake case name, follow the name with `as` and a string literal containing the key:
+//!
+//! ```
+//! micronfig::config! {
+//! 	database_uri as "APP_DATABASE_URI",
+//! 	max_concurrent_users as "APP_MAX_CONCURRENT_USERS": String > u64,
+//! 	shown_alert as "APP_SHOWN_ALERT"?,
+//! }
+//!
+//! # std::env::set_var("APP_DATABASE_URI", "postgres://localhost");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! // Reads the `APP_DATABASE_URI` variable.
+//! println!("{}", database_uri());
+//! # }
+//! ```
+//!
+//! ### Visibility and attributes
+//!
+//! The functions defined by the [`config`] macro are `pub(crate)` by default.
+//!
+//! A different visibility can be specified before the name of each variable, allowing, for example, a crate to export its configuration:
+//!
+//! ```
+//! mod config {
+//! 	micronfig::config! {
+//! 		pub DATABASE_URI,
+//! 		pub(super) APPLICATION_NAME,
+//! 		pub(self) INTERNAL_SECRET,
+//! 	}
+//! }
+//! ```
+//!
+//! The functions defined once per block, `set_cache`, `set_prefix` and `validate`, have the visibility specified by the `#![visibility(...)]` setting instead, so that a crate sharing its configuration can let the applications using it customize and validate it:
+//!
+//! ```
+//! mod config {
+//! 	micronfig::config! {
+//! 		#![visibility(pub)]
+//!
+//! 		pub DATABASE_URI?,
+//! 	}
+//! }
+//!
+//! # if cfg!(feature = "envvars") {
+//! config::set_cache(micronfig::cache::Cache::builder().envvars().build())
+//! 	.expect("cache to be set before the configuration is accessed");
+//! # }
+//! ```
+//!
+//! Attributes, including documentation comments, can be specified before each variable as well, and are applied to all three of its functions, `NAME`, `try_NAME` and `NAME_source`:
+//!
+//! ```
+//! micronfig::config! {
+//! 	/// The URI of the database to connect to.
+//! 	DATABASE_URI,
+//! 	#[cfg(feature = "telemetry")]
+//! 	TELEMETRY_ENDPOINT,
+//! 	#[deprecated = "use DATABASE_URI instead"]
+//! 	DATABASE_URL?,
+//! }
+//! ```
+//!
+//! ### Key prefix
+//!
+//! A prefix can be prepended to the keys of all variables of a block by starting it with the `#![prefix = "..."]` setting:
+//!
+//! ```
+//! micronfig::config! {
+//! 	#![prefix = "ANGY_"]
+//!
+//! 	// Reads the `ANGY_TOKEN` variable.
+//! 	TOKEN,
+//! 	// Reads the `ANGY_APP_ID` variable.
+//! 	app_id as "APP_ID": String > u64,
+//! }
+//! ```
+//!
+//! The prefix can be overridden at runtime via the `set_prefix` function, which is defined in every block and must be called before any of its variables is accessed.
+//!
+//! Libraries can allow the applications using them to customize the prefix of their variables by exporting it with the `#![visibility(...)]` setting, which specifies the visibility of the functions defined once per block, and defaults to `pub(crate)`:
+//!
+//! ```
+//! mod config {
+//! 	micronfig::config! {
+//! 		#![prefix = "ANGY_"]
+//! 		#![visibility(pub)]
+//!
+//! 		pub TOKEN,
+//! 	}
+//! }
+//!
+//! # std::env::set_var("MYAPP_TOKEN", "abcdef");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! // Reads the `MYAPP_TOKEN` variable.
+//! config::set_prefix("MYAPP_")
+//! 	.expect("prefix to be set before the configuration is accessed");
+//! assert_eq!(config::TOKEN(), "abcdef");
+//! # }
+//! ```
+//!
 //! ### Conversions
 //!
 //! All variables are read from their source as strings; therefore, the following explicit syntax for defining them is supported:
@@ -88,8 +194,9 @@
 //! | Trait | Symbol | Notes |
 //! |---|---|---|
 //! | [`From`] | `->` |  |
-//! | [`TryFrom`] | `=>` | Will panic if the conversion fails. |
-//! | [`std::str::FromStr`] | `>` | Will panic if the parsing fails. |
+//! | [`TryFrom`] | `=>` | Will panic if the conversion fails, see [handling errors](#handling-errors). |
+//! | [`std::str::FromStr`] | `>` | Will panic if the parsing fails, see [handling errors](#handling-errors). |
+//! | [`FnOnce`] | `\|>` | See below. |
 //!
 //! The syntax for conversion is as follows:
 //!
@@ -154,6 +261,267 @@
 //! # fn main() {}
 //! ```
 //!
+//! Functions and closures can be used as conversions as well, by prefixing them with `|>` and following them with either `->` and the type they return, or with `=>` and the type contained in the [`Result`] they return:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! fn parse_hex(value: String) -> Result<u32, std::num::ParseIntError> {
+//! 	u32::from_str_radix(&value, 16)
+//! }
+//!
+//! micronfig::config! {
+//! 	// First parse the string as an u64 with FromStr, then pass it to Duration::from_secs.
+//! 	REQUEST_TIMEOUT: String > u64 |> Duration::from_secs -> Duration,
+//! 	// Convert the number of minutes to milliseconds by calling a closure.
+//! 	RETRY_DELAY_MINUTES: String > u64 |> |minutes| minutes * 60_000 -> u64,
+//! 	// Convert the string by calling a function which may fail.
+//! 	ACCENT_COLOR: String |> crate::parse_hex => u32,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! ### Binary and non-UTF-8 values
+//!
+//! Values which are not valid UTF-8, such as environment variables containing non-Unicode paths or files containing binary keys, cause an [`Error::Source`] when read as strings.
+//!
+//! To read them without corrupting them, use [`std::ffi::OsString`], [`std::path::PathBuf`] or [`Vec<u8>`] as the first type instead of [`String`], as allowed by the [`source::FromRaw`] trait:
+//!
+//! ```
+//! use std::ffi::OsString;
+//! use std::path::PathBuf;
+//!
+//! micronfig::config! {
+//! 	DATA_DIRECTORY: PathBuf,
+//! 	DISPLAY_NAME: OsString,
+//! 	TLS_KEY: Vec<u8>,
+//! }
+//! ```
+//!
+//! Unlike other values, [`Vec<u8>`] ones are never trimmed unless a `#[config(trim = "...")]` option is specified, as the last byte of a binary file might be a newline.
+//!
+//! ### Default values
+//!
+//! Required variables can be given a default value by suffixing an equals sign `=` followed by it, making them fall back to it instead of panicking if their value is missing.
+//!
+//! String literals are used in place of the missing value *before* the conversion chain is applied, while any other expression is used in place of the missing value *after* the conversion chain is applied, and must therefore be of the final type:
+//!
+//! ```
+//! micronfig::config! {
+//! 	// the default is a string, so it is parsed like any other value would be
+//! 	LISTEN_PORT: String > u16 = "8080",
+//! 	// the default is an u16, so it is returned as-is
+//! 	METRICS_PORT: String > u16 = 9090,
+//! 	// the default is a string, and so is the final type
+//! 	LOG_LEVEL = "info",
+//! }
+//!
+//! # std::env::remove_var("LISTEN_PORT");
+//! # std::env::remove_var("METRICS_PORT");
+//! # std::env::remove_var("LOG_LEVEL");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! assert_eq!(LISTEN_PORT(), &8080);
+//! assert_eq!(METRICS_PORT(), &9090);
+//! assert_eq!(LOG_LEVEL(), "info");
+//! # }
+//! ```
+//!
+//! Optional variables cannot have a default value.
+//!
+//! ### Constraints
+//!
+//! Variables can be required to satisfy some constraints by suffixing them with one or more `where` clauses, which are checked after the conversion chain and the eventual default value are applied:
+//!
+//! | Constraint | Example | Notes |
+//! |---|---|---|
+//! | Range | `where 1024..=65535` | Any expression evaluating to a [`std::ops::RangeBounds`]. |
+//! | Set | `where ["debug", "info", "warn"]` | An array of the allowed values. |
+//! | Pattern | `where "^[a-z]+$"` | A string literal containing a regular expression, which requires the `regex` crate feature, and is validated at compile-time. |
+//! | Predicate | `where \|value\| value.is_empty()` | A function or closure taking a reference to the value and returning a [`bool`]. |
+//!
+//! If the value does not satisfy a constraint, it is treated as an error, like a failed conversion would be:
+//!
+//! ```
+//! fn is_even(value: &u64) -> bool {
+//! 	value % 2 == 0
+//! }
+//!
+//! micronfig::config! {
+//! 	LISTEN_PORT: String > u16 = 8080 where 1024..=65535,
+//! 	LOG_LEVEL = "info" where ["debug", "info", "warn"],
+//! 	WORKERS: String > u64 where 1.. where crate::is_even,
+//! }
+//!
+//! # fn main() {
+//! # std::env::set_var("LISTEN_PORT", "80");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! assert!(matches!(try_LISTEN_PORT(), Err(micronfig::Error::Constraint { .. })));
+//! # }
+//! # }
+//! ```
+//!
+//! ### Handling errors
+//!
+//! For each variable, an additional function prefixed with `try_` is defined, which returns a [`Result`] containing a `&'static` reference to either the value or to the [`Error`] that prevented its retrieval, instead of panicking:
+//!
+//! ```
+//! micronfig::config! {
+//! 	MAX_CONCURRENT_USERS: String > u64,
+//! }
+//!
+//! # std::env::set_var("MAX_CONCURRENT_USERS", "many");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! match try_MAX_CONCURRENT_USERS() {
+//! 	Ok(value) => println!("Up to {value} users can connect at the same time"),
+//! 	Err(micronfig::Error::Conversion { .. }) => println!("MAX_CONCURRENT_USERS is not a number"),
+//! 	Err(error) => println!("{error}"),
+//! }
+//! # }
+//! ```
+//!
+//! If the error returned by a failed conversion implements [`std::error::Error`], it is exposed as the [`std::error::Error::source`] of the [`Error`], so that the chain of causes can be walked; otherwise, only its [`Debug`](std::fmt::Debug) representation is kept, see [`error::ConversionError`].
+//!
+//! ### Value provenance
+//!
+//! For each variable, an additional function suffixed with `_source` is defined as well, which returns the [`source::Provenance`] of its value, describing the source it was retrieved from, and, if applicable, the file and line it was read from:
+//!
+//! ```
+//! micronfig::config! {
+//! 	DATABASE_URI,
+//! 	LOG_LEVEL = "info",
+//! }
+//!
+//! # std::env::set_var("DATABASE_URI", "postgres://localhost");
+//! # std::env::remove_var("DATABASE_URI_FILE");
+//! # std::env::remove_var("LOG_LEVEL");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! // Prints `envvars`.
+//! println!("{}", DATABASE_URI_source().unwrap());
+//! // Prints `default`.
+//! println!("{}", LOG_LEVEL_source().unwrap());
+//! # }
+//! ```
+//!
+//! The provenance of the value is also included in the [`Error`]s referring to it, and therefore in the messages of the panics caused by them.
+//!
+//! ### Validating all variables at once
+//!
+//! Since variables are lazily initialized, a misconfiguration might go unnoticed until the function of the affected variable is called for the first time.
+//!
+//! To detect it as early as possible, a `validate` function is defined as well, which retrieves the values of all variables of the block, and returns an [`error::Report`] listing every error encountered:
+//!
+//! ```
+//! micronfig::config! {
+//! 	DATABASE_URI,
+//! 	MAX_CONCURRENT_USERS: String > u64,
+//! }
+//!
+//! # std::env::remove_var("DATABASE_URI");
+//! # std::env::set_var("MAX_CONCURRENT_USERS", "many");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! if let Err(report) = validate() {
+//! 	// Prints both the error about DATABASE_URI and the one about MAX_CONCURRENT_USERS.
+//! 	eprintln!("{report}");
+//! # 	assert_eq!(report.errors.len(), 2);
+//! }
+//! # }
+//! ```
+//!
+//! Like `set_prefix`, `validate` has the visibility specified by the `#![visibility(...)]` setting, so that an application can validate the configuration of the libraries it uses at startup:
+//!
+//! ```
+//! mod library {
+//! 	micronfig::config! {
+//! 		#![visibility(pub)]
+//!
+//! 		pub DATABASE_URI?,
+//! 	}
+//! }
+//!
+//! library::validate()
+//! 	.expect("configuration of the library to be valid");
+//! ```
+//!
+//! ### Instances
+//!
+//! The functions defined by the [`config`] macro always read from the same global [`cache::Cache`], making it impossible to retrieve the same variables from different sources in the same process, like it might be needed with multiple tenants or in tests.
+//!
+//! The `#![instance(...)]` setting additionally defines a struct with the given name, which has a field for each variable of the block, and a `from_cache` function, which retrieves the value of every field from the given [`cache::Cache`]:
+//!
+//! ```
+//! micronfig::config! {
+//! 	#![prefix = "APP_"]
+//! 	#![instance(#[derive(Debug)] pub Settings)]
+//!
+//! 	DATABASE_URI,
+//! 	PORT: String > u16 = 8080,
+//! }
+//!
+//! # std::env::set_var("APP_DATABASE_URI", "postgres://localhost");
+//! # std::env::remove_var("APP_PORT");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! let cache = micronfig::cache::Cache::new();
+//! let settings = Settings::from_cache(&cache).unwrap();
+//! assert_eq!(settings.PORT, 8080);
+//! # }
+//! ```
+//!
+//! The struct is `pub(crate)` by default, and its fields have the same visibility as the functions of their variables.
+//!
+//! ### Configuration structs
+//!
+//! As an alternative to the [`config`] macro, the [`Config`] derive macro can be applied to a struct with named fields, allowing configuration to be retrieved as a single value instead of through global functions.
+//!
+//! It implements a `from_cache` function, which retrieves the value of every field from the given [`cache::Cache`], returning the first [`Error`] encountered, if any.
+//!
+//! Fields can be customized with the `#[config(...)]` attribute, which accepts the following options:
+//!
+//! | Option | Example | Equivalent to |
+//! |---|---|---|
+//! | `key` | `#[config(key = "APP_DATABASE_URI")]` | `as "APP_DATABASE_URI"` |
+//! | `optional` | `#[config(optional)]` | `?` |
+//! | `conversion` | `#[config(conversion = String > u16)]` | `: String > u16` |
+//! | `default` | `#[config(default = 8080)]` | `= 8080` |
+//! | `where` | `#[config(where = 1024..=65535)]` | `where 1024..=65535` |
+//! | `trim` | `#[config(trim = "whitespace")]` | `#[config(trim = "whitespace")]` |
+//!
+//! If no `conversion` is specified, the value is parsed from a [`String`] into the type of the field, or into the type wrapped in its [`Option`] if it is `optional`, as if `conversion = String > Type` was specified; [`String`] fields are used as-is.
+//!
+//! The `where` option can be repeated to specify multiple constraints.
+//!
+//! If no `key` is specified, the name of the field in uppercase is used; additionally, a prefix can be prepended to all keys with the `#[config(prefix = "...")]` attribute on the struct itself:
+//!
+//! ```
+//! #[derive(micronfig::Config)]
+//! #[config(prefix = "APP_")]
+//! struct Settings {
+//! 	// Reads the `APP_DATABASE_URI` variable.
+//! 	database_uri: String,
+//! 	// Reads the `APP_PORT` variable, and parses it into a `u16`.
+//! 	#[config(default = 8080, where = 1024..)]
+//! 	port: u16,
+//! 	// Reads the `APP_ALERT` variable.
+//! 	#[config(key = "ALERT", optional)]
+//! 	shown_alert: Option<String>,
+//! }
+//!
+//! # std::env::set_var("APP_DATABASE_URI", "postgres://localhost");
+//! # std::env::remove_var("APP_PORT");
+//! #
+//! # if cfg!(feature = "envvars") {
+//! let cache = micronfig::cache::Cache::new();
+//! let settings = Settings::from_cache(&cache).unwrap();
+//! assert_eq!(settings.port, 8080);
+//! # }
+//! ```
+//!
 //! ## Crate features
 //!
 //! ### Value sources
@@ -165,10 +533,127 @@
 //! | Feature | Description | Use case |
 //! |---|---|---|
 //! | `envfiles` | Contents of the file at the path indicated by the `{NAME}_FILE` environment variable. | Docker [configs](https://docs.docker.com/engine/swarm/configs/) and [secrets](https://docs.docker.com/engine/swarm/secrets/). |
+//! | `credentials` | Contents of the `$CREDENTIALS_DIRECTORY/{NAME}` file. | systemd [credentials](https://systemd.io/CREDENTIALS/) passed with `LoadCredential=` and `SetCredential=`. |
 //! | `envvars` | The `{NAME}` environment variable. | Most command-line applications. |
-//! | `envdot` | The `.env` and `.env.local` files in the current working directory. | Application development. |
+//! | `envdir` | Contents of the `/run/secrets/{NAME}` file. | Docker [secrets](https://docs.docker.com/engine/swarm/secrets/) and Kubernetes [secrets](https://kubernetes.io/docs/concepts/configuration/secret/) mounted as volumes. |
+//! | `envdot` | The `.env.{APP_ENV}.local`, `.env.local`, `.env.{APP_ENV}` and `.env` files in the current working directory, where `{APP_ENV}` is the value of the `APP_ENV` environment variable; `.env.local` is skipped if it is `test`. | Application development. |
+//!
+//! By default, all of them are enabled, except for `credentials` and `envdir`.
+//!
+//! A single trailing newline is removed from the contents of `{NAME}_FILE`, `credentials` and `envdir` files, so that secrets created with `echo token > secret` work as expected; this can be changed for all variables with [`cache::CacheBuilder::trim`], or for a single one with the `#[config(trim = "...")]` option:
+//!
+//! | Mode | [`source::Trim`] | Contents |
+//! |---|---|---|
+//! | `none` | `None` | Used as-is. |
+//! | `newline` | `Newline` | A single trailing `\n` or `\r\n` is removed; the default. |
+//! | `whitespace` | `Whitespace` | All leading and trailing whitespace is removed. |
+//! | `first_line` | `FirstLine` | Only the first line is used. |
+//!
+//! ```
+//! micronfig::config! {
+//! 	#[config(trim = "whitespace")]
+//! 	DATABASE_PASSWORD?,
+//! }
+//! ```
 //!
-//! By default, all of them are enabled.
+//! The directories the `envdir` source looks for files in, and how it maps variable names to file names, can be changed with [`cache::CacheBuilder::envdir`] and [`cache::CacheBuilder::envdir_mapped`]; files whose names start with `.`, such as the `..data` symlink Kubernetes uses to update mounted volumes, are ignored:
+//!
+//! | [`envdir::Mapping`] | File name for `DATABASE_PASSWORD` |
+//! |---|---|
+//! | `Exact` | `DATABASE_PASSWORD`; the default. |
+//! | `Lowercase` | `database_password` |
+//! | `Kebab` | `database-password` |
+//!
+//! ```
+//! # #[cfg(feature = "envdir")]
+//! let cache = micronfig::cache::Cache::builder()
+//! 	.envdir_mapped(["/etc/app/secrets", "/etc/app/config"], micronfig::envdir::Mapping::Kebab)
+//! 	.build();
+//! ```
+//!
+//! The same mappings can be used for `credentials` with [`cache::CacheBuilder::credentials_mapped`], for example to read `database_password` credentials:
+//!
+//! ```
+//! # #[cfg(feature = "credentials")]
+//! let cache = micronfig::cache::Cache::builder()
+//! 	.credentials_mapped(micronfig::credentials::Mapping::Lowercase)
+//! 	.build();
+//! ```
+//!
+//! A second mapping can be tried if no credential is found with the first one with [`cache::CacheBuilder::credentials_fallback`], for example to read `DATABASE_PASSWORD` credentials, or `database_password` ones if they are missing:
+//!
+//! ```
+//! # #[cfg(feature = "credentials")]
+//! let cache = micronfig::cache::Cache::builder()
+//! 	.credentials_fallback(micronfig::credentials::Mapping::Exact, micronfig::credentials::Mapping::Lowercase)
+//! 	.build();
+//! ```
+//!
+//! Values in `.env` files can reference other variables with `$VAR`, `${VAR}` or `${VAR:-default}`; see [`envdot::EnvDot`] for details.
+//!
+//! The directory the `.env` files are looked for in can be changed with the `#![envdot_discovery = "..."]` setting, or with [`cache::CacheBuilder::envdot_discover`]:
+//!
+//! | Mode | [`envdot::Discovery`] | Directory |
+//! |---|---|---|
+//! | `cwd` | `WorkingDirectory` | The current working directory; the default. |
+//! | `parents` | `Parents` | The closest of the current working directory and its parents containing a `.env` file. |
+//! | `executable` | `Executable` | The directory containing the executable. |
+//! | `manifest` | `Directory(env!("CARGO_MANIFEST_DIR").into())` | The directory of the crate, determined at compile-time. |
+//!
+//! ```
+//! # #[cfg(feature = "envdot")]
+//! micronfig::config! {
+//! 	#![envdot_discovery = "parents"]
+//!
+//! 	DATABASE_URI?,
+//! }
+//! ```
+//!
+//! Additional `.env` files can be registered with the `#![envdot = "..."]` setting, which can be repeated, and whose paths are relative to the discovered directory; they have a lower priority than the default ones, which can be disabled with `#![envdot_defaults = false]`:
+//!
+//! ```
+//! # #[cfg(feature = "envdot")]
+//! micronfig::config! {
+//! 	#![envdot_defaults = false]
+//! 	#![envdot = "config/app.env"]
+//!
+//! 	DATABASE_URI?,
+//! }
+//! ```
+//!
+//! `.env` files can also be edited programmatically, preserving their comments and formatting, with [`envdot::document::Document`].
+//!
+//! Malformed lines in `.env` files are silently skipped; [`cache::CacheBuilder::strict`] makes them an error instead, while [`cache::CacheBuilder::lenient`] allows them to be reported, for example as warnings including their position.
+//!
+//! Additional sources can be defined by implementing the [`source::Source`] trait, and can be registered in a [`cache::Cache`] via [`cache::Cache::register`].
+//!
+//! ### Source priority
+//!
+//! The sources and their priority can be changed at runtime by creating a [`cache::Cache`] with a [`cache::CacheBuilder`], and passing it to the `set_cache` function, which is defined in every [`config`] block and must be called before any of its variables is accessed:
+//!
+//! ```
+//! micronfig::config! {
+//! 	DATABASE_URI?,
+//! }
+//!
+//! # if cfg!(all(feature = "envvars", feature = "envdot")) {
+//! // Let `.env` files override the environment variables during development.
+//! let cache = if cfg!(debug_assertions) {
+//! 	micronfig::cache::Cache::builder()
+//! 		.envdot_cwd()
+//! 		.envvars()
+//! 		.build()
+//! } else {
+//! 	micronfig::cache::Cache::builder()
+//! 		.envvars()
+//! 		.envdot_cwd()
+//! 		.build()
+//! };
+//!
+//! set_cache(cache)
+//! 	.expect("cache to be set before the configuration is accessed");
+//! # }
+//! ```
 //!
 
 #![doc(html_logo_url = "https://raw.githubusercontent.com/Steffo99/micronfig/main/.media/icon-128x128_round.png")]
@@ -176,14 +661,36 @@
 /// The macro described at the crate's root.
 pub use micronfig_macros::config;
 
+/// The derive macro described at the crate's root.
+pub use micronfig_macros::Config;
+
 pub mod cache;
 
+pub mod source;
+
+pub mod error;
+
+pub use error::Error;
+
+pub mod constraints;
+
 #[cfg(feature = "envvars")]
 pub mod envvars;
 
 #[cfg(feature = "envfiles")]
 pub mod envfiles;
 
+#[cfg(feature = "credentials")]
+pub mod credentials;
+
+#[cfg(feature = "envdir")]
+pub mod envdir;
+
+// Without the `envdir` feature, only the parts of it `credentials` is built upon are used.
+#[cfg(all(feature = "credentials", not(feature = "envdir")))]
+#[allow(dead_code)]
+mod envdir;
+
 #[cfg(feature = "envdot")]
 pub mod envdot;
 
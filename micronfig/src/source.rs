@@ -0,0 +1,372 @@
+//! **Private**; definition of [`Source`] and [`Provenance`].
+
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Debug, Display, Formatter};
+use std::path::PathBuf;
+use crate::cache::Resolver;
+
+/// An origin of configuration values, which can be registered in a [`Cache`](crate::cache::Cache).
+///
+/// Every `env*` feature provides its own implementation, but applications can implement it as well to retrieve values from anywhere else.
+///
+/// All methods except [`Source::name`] have a default implementation, but at least one of [`Source::get`] and [`Source::lookup_raw_with`] must be implemented, as their defaults are built on each other.
+///
+/// ```
+/// use std::ffi::{OsStr, OsString};
+/// use micronfig::source::Source;
+///
+/// /// A source always returning the same value, whichever the key.
+/// #[derive(Debug)]
+/// struct Constant(String);
+///
+/// impl Source for Constant {
+/// 	fn name(&self) -> String {
+/// 		"constant".to_string()
+/// 	}
+///
+/// 	fn get(&self, _key: &OsStr) -> std::io::Result<Option<String>> {
+/// 		Ok(Some(self.0.clone()))
+/// 	}
+/// }
+///
+/// let mut cache = micronfig::cache::Cache::default();
+/// cache.register(Constant("garas".to_string()));
+/// ```
+pub trait Source: Debug + Send + Sync {
+	/// A human-readable name of the source, to be used in diagnostics.
+	fn name(&self) -> String;
+
+	/// Get the value of the variable with the given key, if the source has one.
+	///
+	/// Returns an [`std::io::Error`] if the source is unable to read the value.
+	///
+	/// Calls [`Source::lookup`] and discards the [`Provenance`] by default.
+	fn get(&self, key: &OsStr) -> std::io::Result<Option<String>> {
+		Ok(self.lookup(key)?.map(|(value, _)| value))
+	}
+
+	/// Describe where the values of this source come from, without referring to any specific variable.
+	///
+	/// Returns a [`Provenance`] containing only [`Source::name`] by default.
+	fn provenance(&self) -> Provenance {
+		Provenance::new(self.name())
+	}
+
+	/// Get the value of the variable with the given key, if the source has one, along with the [`Provenance`] of the value.
+	///
+	/// Calls [`Source::lookup_with`] with a [`Resolver`] which never finds any other variable by default.
+	fn lookup(&self, key: &OsStr) -> std::io::Result<Option<(String, Provenance)>> {
+		self.lookup_with(key, &mut Resolver::detached())
+	}
+
+	/// Like [`Source::lookup`], but allowing the source to retrieve the values of other variables from the [`Cache`](crate::cache::Cache) it is registered in via the given [`Resolver`].
+	///
+	/// Calls [`Source::lookup_raw_with`] and requires its value to be valid UTF-8 by default.
+	fn lookup_with(&self, key: &OsStr, resolver: &mut Resolver<'_>) -> std::io::Result<Option<(String, Provenance)>> {
+		self.lookup_raw_with(key, None, resolver)?
+			.map(|(value, provenance)| Ok((value.into_string()?, provenance)))
+			.transpose()
+	}
+
+	/// Like [`Source::lookup_with`], but returning the value as [`Raw`], without requiring it to be valid UTF-8.
+	///
+	/// `trim` specifies how file-based sources should trim the value, if not as they were configured to, such as when a specific [`Trim`] is requested via [`Cache::try_lookup_trimmed`](crate::cache::Cache::try_lookup_trimmed); other sources can ignore it.
+	///
+	/// Calls [`Source::get`] and pairs its value with [`Source::provenance`] by default; sources able to locate individual values more precisely, to read values which are not valid UTF-8, or whose values can reference other variables should override it.
+	fn lookup_raw_with(&self, key: &OsStr, trim: Option<Trim>, resolver: &mut Resolver<'_>) -> std::io::Result<Option<(Raw, Provenance)>> {
+		let _ = (trim, resolver);
+		Ok(self.get(key)?.map(|value| (Raw::Utf8(value), self.provenance())))
+	}
+
+	/// Enumerate the keys of all variables the source has a value for, if the source supports it.
+	///
+	/// Returns [`None`] by default.
+	fn keys(&self) -> Option<Vec<OsString>> {
+		None
+	}
+}
+
+/// A value retrieved from a [`Source`], which might not be valid UTF-8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Raw {
+	/// A value which is valid UTF-8.
+	Utf8(String),
+
+	/// A platform string, such as the value of an environment variable.
+	Os(OsString),
+
+	/// Arbitrary bytes, such as the contents of a file.
+	Bytes(Vec<u8>),
+}
+
+impl Raw {
+	/// Convert the value to a [`String`], returning an [`std::io::Error`] if it is not valid UTF-8.
+	pub fn into_string(self) -> std::io::Result<String> {
+		match self {
+			Raw::Utf8(value) => Ok(value),
+			Raw::Os(value) => value.into_string()
+				.map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "value is not valid Unicode")),
+			Raw::Bytes(value) => String::from_utf8(value)
+				.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+		}
+	}
+
+	/// Convert the value to an [`OsString`].
+	///
+	/// Never fails on Unix; on other platforms, returns an [`std::io::Error`] if the value consists of bytes which are not valid UTF-8.
+	pub fn into_os_string(self) -> std::io::Result<OsString> {
+		match self {
+			Raw::Utf8(value) => Ok(value.into()),
+			Raw::Os(value) => Ok(value),
+			#[cfg(unix)]
+			Raw::Bytes(value) => Ok(std::os::unix::ffi::OsStringExt::from_vec(value)),
+			#[cfg(not(unix))]
+			Raw::Bytes(value) => Raw::Bytes(value).into_string().map(OsString::from),
+		}
+	}
+
+	/// Convert the value to bytes; platform strings are converted to their [encoded bytes](OsString::into_encoded_bytes).
+	pub fn into_bytes(self) -> Vec<u8> {
+		match self {
+			Raw::Utf8(value) => value.into_bytes(),
+			Raw::Os(value) => value.into_encoded_bytes(),
+			Raw::Bytes(value) => value,
+		}
+	}
+}
+
+/// A type which can be created from a [`Raw`] value, and can therefore be used as the first type of a [`config`](crate::config) item.
+#[diagnostic::on_unimplemented(
+	message = "`{Self}` cannot be the first type of a configuration variable",
+	note = "the first type must be `String`, `OsString`, `PathBuf` or `Vec<u8>`; other types can be reached with a conversion, such as `String > {Self}`",
+)]
+pub trait FromRaw: Sized {
+	/// How file-based sources should trim the value, unless a different [`Trim`] is specified for the item; if [`None`], they trim it as they were configured to.
+	const TRIM: Option<Trim> = None;
+
+	/// Create the value, returning an [`std::io::Error`] if the raw value cannot be represented by this type.
+	fn from_raw(raw: Raw) -> std::io::Result<Self>;
+}
+
+impl FromRaw for String {
+	fn from_raw(raw: Raw) -> std::io::Result<Self> {
+		raw.into_string()
+	}
+}
+
+impl FromRaw for OsString {
+	fn from_raw(raw: Raw) -> std::io::Result<Self> {
+		raw.into_os_string()
+	}
+}
+
+impl FromRaw for PathBuf {
+	fn from_raw(raw: Raw) -> std::io::Result<Self> {
+		raw.into_os_string().map(PathBuf::from)
+	}
+}
+
+impl FromRaw for Vec<u8> {
+	/// Binary contents are used as-is, as their last byte might be a newline.
+	const TRIM: Option<Trim> = Some(Trim::None);
+
+	fn from_raw(raw: Raw) -> std::io::Result<Self> {
+		Ok(raw.into_bytes())
+	}
+}
+
+/// Description of where a value was retrieved from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Provenance {
+	/// The name of the [`Source`] the value was retrieved from.
+	pub source: String,
+
+	/// The path of the file the value was read from, if any.
+	pub path: Option<PathBuf>,
+
+	/// The line of the file the value was defined at, if known.
+	pub line: Option<usize>,
+}
+
+impl Provenance {
+	/// Create a provenance referring only to the source with the given name.
+	pub fn new(source: impl Into<String>) -> Self {
+		Self { source: source.into(), path: None, line: None }
+	}
+
+	/// The provenance of values coming from the default specified in the [`config`](crate::config) macro.
+	pub fn default_value() -> Self {
+		Self::new("default")
+	}
+
+	/// Add the path of the file the value was read from.
+	pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.path = Some(path.into());
+		self
+	}
+
+	/// Add the line of the file the value was defined at.
+	pub fn with_line(mut self, line: usize) -> Self {
+		self.line = Some(line);
+		self
+	}
+}
+
+impl Display for Provenance {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.source)?;
+		match (&self.path, self.line) {
+			(Some(path), Some(line)) => write!(f, " ({}:{line})", path.display()),
+			(Some(path), None) => write!(f, " ({})", path.display()),
+			(None, Some(line)) => write!(f, " (line {line})"),
+			(None, None) => Ok(()),
+		}
+	}
+}
+
+/// How the contents of files read by file-based sources, such as [`EnvFiles`](crate::envfiles::EnvFiles), should be trimmed before being used as values.
+///
+/// Useful as files are usually terminated by a newline, which for example `echo token > secret` adds to the value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Trim {
+	/// Use the contents as-is.
+	None,
+
+	/// Remove a single trailing `\n` or `\r\n`, if present.
+	#[default]
+	Newline,
+
+	/// Remove all leading and trailing whitespace.
+	Whitespace,
+
+	/// Use only the first line, without its terminator.
+	FirstLine,
+}
+
+impl Trim {
+	/// Trim the given contents.
+	pub fn apply(&self, mut contents: String) -> String {
+		match self {
+			Trim::None => {},
+			Trim::Newline => {
+				if contents.ends_with('\n') {
+					contents.pop();
+					if contents.ends_with('\r') {
+						contents.pop();
+					}
+				}
+			},
+			Trim::Whitespace => {
+				contents.truncate(contents.trim_end().len());
+				contents.drain(..contents.len() - contents.trim_start().len());
+			},
+			Trim::FirstLine => {
+				let len = contents.find('\n').unwrap_or(contents.len());
+				contents.truncate(len);
+				if contents.ends_with('\r') {
+					contents.pop();
+				}
+			},
+		}
+		contents
+	}
+
+	/// Decode the given contents as UTF-8 if possible, and trim them like [`Trim::apply`] does; otherwise, keep them as bytes and trim them like [`Trim::apply_bytes`] does.
+	pub fn decode(&self, contents: Vec<u8>) -> Raw {
+		// Text is trimmed as such, so that Unicode whitespace is trimmed too.
+		match String::from_utf8(contents) {
+			Ok(contents) => Raw::Utf8(self.apply(contents)),
+			Err(err) => Raw::Bytes(self.apply_bytes(err.into_bytes())),
+		}
+	}
+
+	/// Trim the given contents like [`Trim::apply`] does, but treating only ASCII characters as whitespace.
+	pub fn apply_bytes(&self, mut contents: Vec<u8>) -> Vec<u8> {
+		match self {
+			Trim::None => {},
+			Trim::Newline => {
+				if contents.ends_with(b"\n") {
+					contents.pop();
+					if contents.ends_with(b"\r") {
+						contents.pop();
+					}
+				}
+			},
+			Trim::Whitespace => {
+				let start = contents.iter().position(|c| !c.is_ascii_whitespace()).unwrap_or(contents.len());
+				let end = contents.iter().rposition(|c| !c.is_ascii_whitespace()).map_or(start, |index| index + 1);
+				contents.truncate(end);
+				contents.drain(..start);
+			},
+			Trim::FirstLine => {
+				let len = contents.iter().position(|c| *c == b'\n').unwrap_or(contents.len());
+				contents.truncate(len);
+				if contents.ends_with(b"\r") {
+					contents.pop();
+				}
+			},
+		}
+		contents
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_name() {
+		let provenance = Provenance::new("envvars");
+		assert_eq!(provenance.to_string(), "envvars");
+	}
+
+	#[test]
+	fn display_path_line() {
+		let provenance = Provenance::new("envdot")
+			.with_path("./.env")
+			.with_line(3);
+		assert_eq!(provenance.to_string(), "envdot (./.env:3)");
+	}
+
+	#[test]
+	fn trim() {
+		let contents = " token\r\nsecond line\n\n";
+		assert_eq!(Trim::None.apply(contents.to_string()), contents);
+		assert_eq!(Trim::Newline.apply(contents.to_string()), " token\r\nsecond line\n");
+		assert_eq!(Trim::Whitespace.apply(contents.to_string()), "token\r\nsecond line");
+		assert_eq!(Trim::FirstLine.apply(contents.to_string()), " token");
+
+		assert_eq!(Trim::Newline.apply("token\r\n".to_string()), "token");
+		assert_eq!(Trim::Newline.apply("token".to_string()), "token");
+		assert_eq!(Trim::FirstLine.apply("token".to_string()), "token");
+	}
+
+	#[test]
+	fn trim_bytes() {
+		let contents = b" \xFF\r\nsecond line\n\n".to_vec();
+		assert_eq!(Trim::None.apply_bytes(contents.clone()), contents);
+		assert_eq!(Trim::Newline.apply_bytes(contents.clone()), b" \xFF\r\nsecond line\n");
+		assert_eq!(Trim::Whitespace.apply_bytes(contents.clone()), b"\xFF\r\nsecond line");
+		assert_eq!(Trim::FirstLine.apply_bytes(contents.clone()), b" \xFF");
+		assert_eq!(Trim::Whitespace.apply_bytes(b" \n ".to_vec()), b"");
+	}
+
+	#[test]
+	fn raw_string() {
+		assert_eq!(String::from_raw(Raw::Utf8("garas".into())).unwrap(), "garas");
+		assert_eq!(String::from_raw(Raw::Bytes(b"garas".to_vec())).unwrap(), "garas");
+		assert_eq!(String::from_raw(Raw::Bytes(b"\xFF".to_vec())).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn raw_non_unicode() {
+		use std::os::unix::ffi::OsStringExt;
+
+		let value = OsString::from_vec(b"garas\xFF".to_vec());
+		assert_eq!(String::from_raw(Raw::Os(value.clone())).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+		assert_eq!(OsString::from_raw(Raw::Bytes(b"garas\xFF".to_vec())).unwrap(), value);
+		assert_eq!(PathBuf::from_raw(Raw::Os(value.clone())).unwrap(), PathBuf::from(&value));
+		assert_eq!(Vec::<u8>::from_raw(Raw::Os(value)).unwrap(), b"garas\xFF");
+	}
+}
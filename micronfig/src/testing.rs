@@ -1,4 +1,4 @@
-/// **Private**; utilities for testing.
+//! **Private**; utilities for testing.
 
 pub fn tempfile_fixture(content: &str) -> tempfile::TempPath {
 	use std::io::Write;
@@ -0,0 +1,165 @@
+//! Implementation of the `Config` derive macro.
+
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, GenericArgument, LitStr, PathArguments, Type, TypePath, Visibility};
+use crate::{ConfigConstraint, ConfigDefault, ConfigItem, ConfigPair, ConfigTrim, Conversion};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let mut prefix: Option<LitStr> = None;
+
+	for attribute in input.attrs.iter().filter(|attribute| attribute.path().is_ident("config")) {
+		attribute.parse_nested_meta(|meta| {
+			if meta.path.is_ident("prefix") {
+				prefix = Some(meta.value()?.parse()?);
+				Ok(())
+			}
+			else {
+				Err(meta.error("unknown attribute; the only valid attribute on structs is `prefix`."))
+			}
+		})?;
+	}
+
+	let prefix = match prefix {
+		Some(prefix) => prefix.value(),
+		None => String::new(),
+	};
+
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(syn::Error::new_spanned(&input.ident, "`Config` can only be derived for structs with named fields.")),
+		},
+		_ => return Err(syn::Error::new_spanned(&input.ident, "`Config` can only be derived for structs.")),
+	};
+
+	let fields_code = fields.iter().map(|field| {
+		let item = field_item(field)?;
+		let identifier = &item.identifier;
+		let resolve_code = item.resolve_code(quote! { cache }, quote! { #prefix });
+
+		Ok(quote! {
+			#identifier: #resolve_code.0,
+		})
+	}).collect::<syn::Result<Vec<_>>>()?;
+
+	let identifier = &input.ident;
+	let visibility = &input.vis;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics #identifier #type_generics #where_clause {
+			/// Retrieve the values of all fields from the given [`micronfig::cache::Cache`], returning the first error encountered.
+			#[allow(dead_code)]
+			#visibility fn from_cache(cache: &micronfig::cache::Cache) -> Result<Self, micronfig::Error> {
+				Ok(Self {
+					#( #fields_code )*
+				})
+			}
+		}
+	})
+}
+
+/// Build the [`ConfigItem`] equivalent to the given field and its `#[config(...)]` attributes.
+fn field_item(field: &Field) -> syn::Result<ConfigItem> {
+	let identifier = field.ident.clone()
+		.expect("named fields to have an identifier");
+
+	let mut key: Option<LitStr> = None;
+	let mut optional = false;
+	let mut conversion: Option<(TypePath, Vec<ConfigPair>)> = None;
+	let mut default: Option<ConfigDefault> = None;
+	let mut constraints: Vec<ConfigConstraint> = Vec::new();
+	let mut trim: Option<ConfigTrim> = None;
+
+	for attribute in field.attrs.iter().filter(|attribute| attribute.path().is_ident("config")) {
+		attribute.parse_nested_meta(|meta| {
+			if meta.path.is_ident("key") {
+				key = Some(meta.value()?.parse()?);
+			}
+			else if meta.path.is_ident("optional") {
+				optional = true;
+			}
+			else if meta.path.is_ident("conversion") {
+				let value = meta.value()?;
+				let first = value.parse()?;
+				let mut types = Vec::new();
+				while ConfigPair::peek(value) {
+					types.push(value.parse()?);
+				}
+				conversion = Some((first, types));
+			}
+			else if meta.path.is_ident("default") {
+				default = Some(meta.value()?.parse()?);
+			}
+			else if meta.path.is_ident("where") {
+				constraints.push(meta.value()?.parse()?);
+			}
+			else if meta.path.is_ident("trim") {
+				trim = Some(ConfigTrim::from_lit(&meta.value()?.parse()?)?);
+			}
+			else {
+				return Err(meta.error("unknown attribute; valid attributes on fields are `key`, `optional`, `conversion`, `default`, `where` and `trim`."))
+			}
+			Ok(())
+		})?;
+	}
+
+	if optional && default.is_some() {
+		return Err(syn::Error::new_spanned(&identifier, "optional fields cannot have a default value; remove either `optional` or `default`."))
+	}
+
+	let (first, types) = match conversion {
+		Some(conversion) => conversion,
+		None => field_conversion(field, optional)?,
+	};
+
+	// Fields are snake case, while keys are usually screaming snake case.
+	let key = key.unwrap_or_else(|| LitStr::new(&identifier.to_string().to_uppercase(), identifier.span()));
+
+	Ok(ConfigItem {
+		attributes: Vec::new(),
+		visibility: Visibility::Inherited,
+		identifier,
+		key: Some(key),
+		optional,
+		first,
+		types,
+		default,
+		constraints,
+		trim,
+	})
+}
+
+/// The conversion chain from [`String`] to the type of the given field, or to the type wrapped in its [`Option`] if it is optional, used if no `conversion` is specified.
+fn field_conversion(field: &Field, optional: bool) -> syn::Result<(TypePath, Vec<ConfigPair>)> {
+	let target = match optional {
+		false => &field.ty,
+		true => option_inner(&field.ty)
+			.ok_or_else(|| syn::Error::new_spanned(&field.ty, "optional fields must be of type `Option<...>`."))?,
+	};
+
+	let first: TypePath = syn::parse_quote!(String);
+
+	let types = match target {
+		Type::Path(path) if path.qself.is_none() && path.path.is_ident("String") => Vec::new(),
+		target => vec![ConfigPair { conversion: Conversion::FromStr, r#type: target.clone() }],
+	};
+
+	Ok((first, types))
+}
+
+/// The type wrapped in the given [`Option`] type, if it is one.
+fn option_inner(r#type: &Type) -> Option<&Type> {
+	let segment = match r#type {
+		Type::Path(TypePath { qself: None, path }) => path.segments.last()?,
+		_ => return None,
+	};
+
+	match &segment.arguments {
+		PathArguments::AngleBracketed(arguments) if segment.ident == "Option" && arguments.args.len() == 1 => match arguments.args.first()? {
+			GenericArgument::Type(inner) => Some(inner),
+			_ => None,
+		},
+		_ => None,
+	}
+}
@@ -1,18 +1,70 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
-use syn::{Ident, parse_macro_input, Token, Type, TypePath};
+use syn::{Attribute, Expr, Ident, LitStr, parse_macro_input, Token, Type, TypePath, Visibility};
 use syn::punctuated::Punctuated;
 
-type Config = Punctuated<ConfigItem, Token![,]>;
+mod derive;
+
+struct Config {
+	settings: ConfigSettings,
+	items: Punctuated<ConfigItem, Token![,]>,
+}
+
+#[derive(Clone, Default)]
+struct ConfigSettings {
+	prefix: Option<LitStr>,
+	instance: Option<ConfigInstance>,
+	envdot_discovery: Option<ConfigDiscovery>,
+	envdot: Vec<LitStr>,
+	envdot_defaults: Option<syn::LitBool>,
+	visibility: Option<Visibility>,
+}
+
+#[derive(Clone)]
+struct ConfigInstance {
+	attributes: Vec<Attribute>,
+	visibility: Visibility,
+	identifier: Ident,
+}
+
+#[derive(Clone)]
+enum ConfigDiscovery {
+	/// The current working directory.
+	Cwd,
+	/// The current working directory, or the closest of its parents containing a `.env` file.
+	Parents,
+	/// The directory containing the executable.
+	Executable,
+	/// The directory containing the manifest of the crate invoking the macro, determined at compile-time.
+	Manifest,
+}
 
 #[derive(Clone)]
 struct ConfigItem {
+	attributes: Vec<Attribute>,
+	visibility: Visibility,
 	identifier: Ident,
+	key: Option<LitStr>,
 	optional: bool,
 	first: TypePath,
 	types: Vec<ConfigPair>,
+	default: Option<ConfigDefault>,
+	constraints: Vec<ConfigConstraint>,
+	trim: Option<ConfigTrim>,
+}
+
+#[derive(Clone)]
+enum ConfigTrim {
+	/// Use the contents of files as-is.
+	None,
+	/// Remove a single trailing newline.
+	Newline,
+	/// Remove all leading and trailing whitespace.
+	Whitespace,
+	/// Use only the first line.
+	FirstLine,
 }
 
 #[derive(Clone)]
@@ -21,18 +73,209 @@ struct ConfigPair {
 	r#type: Type,
 }
 
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Clone)]
 enum Conversion {
 	From,
 	TryFrom,
 	FromStr,
+	/// A function returning the converted value.
+	Function(Expr),
+	/// A function returning a [`Result`] containing the converted value.
+	TryFunction(Expr),
+}
+
+#[derive(Clone)]
+enum ConfigDefault {
+	/// A string literal, used in place of the missing value before the conversion chain is applied.
+	Raw(LitStr),
+	/// Any other expression, used in place of the missing value after the conversion chain is applied.
+	Value(Expr),
+}
+
+#[derive(Clone)]
+enum ConfigConstraint {
+	/// A range the value must be contained in.
+	Range(syn::ExprRange),
+	/// An array of values the value must be equal to one of.
+	Set(syn::ExprArray),
+	/// A string literal containing a regular expression the value must match.
+	Pattern(LitStr),
+	/// A function or closure taking a reference to the value and returning whether it is valid.
+	Predicate(Expr),
+}
+
+impl Parse for Config {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let attributes = input.call(Attribute::parse_inner)?;
+		let settings = ConfigSettings::from_attributes(&attributes)?;
+
+		let items = Punctuated::parse_terminated(input)?;
+
+		Ok(Self { settings, items })
+	}
+}
+
+impl ConfigSettings {
+	fn from_attributes(attributes: &[Attribute]) -> syn::Result<Self> {
+		let mut this = Self::default();
+
+		for attribute in attributes {
+			if attribute.path().is_ident("prefix") {
+				if this.prefix.is_some() {
+					return Err(syn::Error::new_spanned(attribute, "the `prefix` setting can only be specified once."))
+				}
+				this.prefix = Some(parse_setting_str(attribute)?);
+			}
+			else if attribute.path().is_ident("instance") {
+				if this.instance.is_some() {
+					return Err(syn::Error::new_spanned(attribute, "the `instance` setting can only be specified once."))
+				}
+				this.instance = Some(attribute.parse_args::<ConfigInstance>()?);
+			}
+			else if attribute.path().is_ident("envdot_discovery") {
+				if this.envdot_discovery.is_some() {
+					return Err(syn::Error::new_spanned(attribute, "the `envdot_discovery` setting can only be specified once."))
+				}
+				this.envdot_discovery = Some(ConfigDiscovery::from_lit(&parse_setting_str(attribute)?)?);
+			}
+			else if attribute.path().is_ident("envdot") {
+				this.envdot.push(parse_setting_str(attribute)?);
+			}
+			else if attribute.path().is_ident("envdot_defaults") {
+				if this.envdot_defaults.is_some() {
+					return Err(syn::Error::new_spanned(attribute, "the `envdot_defaults` setting can only be specified once."))
+				}
+				this.envdot_defaults = Some(parse_setting_bool(attribute)?);
+			}
+			else if attribute.path().is_ident("visibility") {
+				if this.visibility.is_some() {
+					return Err(syn::Error::new_spanned(attribute, "the `visibility` setting can only be specified once."))
+				}
+				this.visibility = Some(attribute.parse_args::<Visibility>()?);
+			}
+			else {
+				return Err(syn::Error::new_spanned(attribute.path(), "unknown setting; valid settings are `prefix`, `instance`, `visibility`, `envdot`, `envdot_defaults` and `envdot_discovery`."))
+			}
+		}
+
+		Ok(this)
+	}
+
+	/// The visibility of the functions defined once per block, defaulting to `pub(crate)` if none was specified.
+	fn visibility(&self) -> Visibility {
+		match &self.visibility {
+			None | Some(Visibility::Inherited) => syn::parse_quote!(pub(crate)),
+			Some(visibility) => visibility.clone(),
+		}
+	}
 }
 
+impl Parse for ConfigInstance {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let attributes = input.call(Attribute::parse_outer)?;
+		let visibility = input.parse::<Visibility>()?;
+		let identifier = input.parse::<Ident>()?;
+
+		Ok(Self { attributes, visibility, identifier })
+	}
+}
+
+impl ConfigDiscovery {
+	fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+		match lit.value().as_str() {
+			"cwd" => Ok(ConfigDiscovery::Cwd),
+			"parents" => Ok(ConfigDiscovery::Parents),
+			"executable" => Ok(ConfigDiscovery::Executable),
+			"manifest" => Ok(ConfigDiscovery::Manifest),
+			_ => Err(syn::Error::new_spanned(lit, "unknown discovery mode; valid modes are `cwd`, `parents`, `executable` and `manifest`.")),
+		}
+	}
+
+	/// An expression evaluating to the equivalent `micronfig::envdot::Discovery`.
+	fn discovery_code(&self) -> proc_macro2::TokenStream {
+		match self {
+			ConfigDiscovery::Cwd => quote! { micronfig::envdot::Discovery::WorkingDirectory },
+			ConfigDiscovery::Parents => quote! { micronfig::envdot::Discovery::Parents },
+			ConfigDiscovery::Executable => quote! { micronfig::envdot::Discovery::Executable },
+			ConfigDiscovery::Manifest => quote! { micronfig::envdot::Discovery::Directory(std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))) },
+		}
+	}
+}
+
+/// Parse the string literal value of a `#![name = "value"]` setting.
+fn parse_setting_str(attribute: &Attribute) -> syn::Result<LitStr> {
+	let meta = attribute.meta.require_name_value()?;
+
+	match &meta.value {
+		Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Ok(lit.clone()),
+		other => Err(syn::Error::new_spanned(other, "expected a string literal.")),
+	}
+}
+
+/// Parse the boolean literal value of a `#![name = value]` setting.
+fn parse_setting_bool(attribute: &Attribute) -> syn::Result<syn::LitBool> {
+	let meta = attribute.meta.require_name_value()?;
+
+	match &meta.value {
+		Expr::Lit(syn::ExprLit { lit: syn::Lit::Bool(lit), .. }) => Ok(lit.clone()),
+		other => Err(syn::Error::new_spanned(other, "expected `true` or `false`.")),
+	}
+}
+
+impl ConfigTrim {
+	fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+		match lit.value().as_str() {
+			"none" => Ok(ConfigTrim::None),
+			"newline" => Ok(ConfigTrim::Newline),
+			"whitespace" => Ok(ConfigTrim::Whitespace),
+			"first_line" => Ok(ConfigTrim::FirstLine),
+			_ => Err(syn::Error::new_spanned(lit, "unknown trim mode; valid modes are `none`, `newline`, `whitespace` and `first_line`.")),
+		}
+	}
+
+	/// An expression evaluating to the equivalent `micronfig::source::Trim`.
+	fn trim_code(&self) -> proc_macro2::TokenStream {
+		match self {
+			ConfigTrim::None => quote! { micronfig::source::Trim::None },
+			ConfigTrim::Newline => quote! { micronfig::source::Trim::Newline },
+			ConfigTrim::Whitespace => quote! { micronfig::source::Trim::Whitespace },
+			ConfigTrim::FirstLine => quote! { micronfig::source::Trim::FirstLine },
+		}
+	}
+}
 
 impl Parse for ConfigItem {
 	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let (options, attributes): (Vec<Attribute>, Vec<Attribute>) = input.call(Attribute::parse_outer)?
+			.into_iter()
+			.partition(|attribute| attribute.path().is_ident("config"));
+
+		let mut trim = None;
+		for option in options.iter() {
+			option.parse_nested_meta(|meta| {
+				if meta.path.is_ident("trim") {
+					trim = Some(ConfigTrim::from_lit(&meta.value()?.parse()?)?);
+					Ok(())
+				}
+				else {
+					Err(meta.error("unknown option; the only valid option on items is `trim`."))
+				}
+			})?;
+		}
+
+		let visibility = input.parse::<Visibility>()?;
 		let identifier = input.parse::<Ident>()?;
 
+		let key = match input.lookahead1().peek(Token![as]) {
+			true => {
+				input.parse::<Token![as]>()
+					.expect("Expected `as`, as it was previously peeked");
+
+				Some(input.parse::<LitStr>()?)
+			},
+			false => None,
+		};
+
 		let optional = input.lookahead1().peek(Token![?]);
 		if optional {
 			input.parse::<Token![?]>()
@@ -47,8 +290,8 @@ impl Parse for ConfigItem {
 				let first = input.parse::<TypePath>()?;
 
 				let mut types = Vec::new();
-				while let Ok(typ) = input.parse::<ConfigPair>() {
-					types.push(typ)
+				while ConfigPair::peek(input) {
+					types.push(input.parse::<ConfigPair>()?)
 				}
 				
 				(first, types)
@@ -61,104 +304,151 @@ impl Parse for ConfigItem {
 			},
 		};
 
-		Ok(Self { identifier, optional, first, types })
-	}
-}
+		let default = match input.peek(Token![=]) && !input.peek(Token![==]) {
+			true => {
+				let eq = input.parse::<Token![=]>()
+					.expect("Expected `=`, as it was previously peeked");
 
-impl Parse for ConfigPair {
-	fn parse(input: ParseStream) -> syn::Result<Self> {
-		let conversion = input.parse::<Conversion>()?;
-		let r#type = input.parse::<Type>()?;
+				if optional {
+					return Err(syn::Error::new_spanned(eq, "optional variables cannot have a default value; remove either the `?` or the `= ...`."))
+				}
 
-		Ok(Self { conversion, r#type })
-	}
-}
+				Some(input.parse::<ConfigDefault>()?)
+			},
+			false => None,
+		};
 
-impl Parse for Conversion {
-	fn parse(input: ParseStream) -> syn::Result<Self> {
-		if input.parse::<Token![->]>().is_ok() {
-			Ok(Conversion::From)
-		}
-		else if input.parse::<Token![=>]>().is_ok() {
-			Ok(Conversion::TryFrom)
-		}
-		else if input.parse::<Token![>]>().is_ok() {
-			Ok(Conversion::FromStr)
-		}
-		else {
-			Err(input.error("cannot determine conversion method to use; valid conversion tokens are `->` (From), `=>` (TryFrom) and `>` (FromStr)."))
+		let mut constraints = Vec::new();
+		while input.peek(Token![where]) {
+			input.parse::<Token![where]>()
+				.expect("Expected `where`, as it was previously peeked");
+
+			constraints.push(input.parse::<ConfigConstraint>()?);
 		}
+
+		Ok(Self { attributes, visibility, identifier, key, optional, first, types, default, constraints, trim })
 	}
 }
 
-#[proc_macro]
-pub fn config(input: TokenStream) -> TokenStream {
-	let input: Config = parse_macro_input!(input with Punctuated::parse_terminated);
-
-	let cache_code = quote! {
-		#[allow(non_snake_case)]
-		fn _cache() -> &'static micronfig::cache::Cache {
-			static LOCK: std::sync::OnceLock<micronfig::cache::Cache> = std::sync::OnceLock::new();
-			
-			LOCK.get_or_init(micronfig::cache::Cache::new)
+impl ConfigItem {
+	/// The visibility of the generated functions, defaulting to `pub(crate)` if none was specified.
+	fn visibility(&self) -> Visibility {
+		match &self.visibility {
+			Visibility::Inherited => syn::parse_quote!(pub(crate)),
+			visibility => visibility.clone(),
 		}
-	};
-
-	let items_code = input.iter().map(|item: &ConfigItem| {
-		let identifier = &item.identifier;
-		let identifier_string = identifier.to_string();
-		
-		let type_first = &item.first;
+	}
 
-		let type_final = match item.types.last() {
+	/// The type of the value after the whole conversion chain is applied.
+	fn type_final(&self) -> proc_macro2::TokenStream {
+		match self.types.last() {
 			Some(pair) => {
 				let typ = pair.r#type.clone();
 				quote! { #typ }
 			},
 			None => {
-				quote! { std::string::String }
+				let typ = &self.first;
+				quote! { #typ }
 			},
-		};
-		let type_final_option = match item.optional {
+		}
+	}
+
+	/// The type of the value returned to the user, wrapped in an [`Option`] if the variable is optional.
+	fn type_final_option(&self) -> proc_macro2::TokenStream {
+		let type_final = self.type_final();
+
+		match self.optional {
 			true => quote! { std::option::Option<#type_final> },
 			false => quote! { #type_final },
+		}
+	}
+
+	/// A block evaluating to the value of the variable, retrieved from `cache` with its key prefixed by `prefix`.
+	///
+	/// Returns early from the surrounding function with a `micronfig::Error` if the value cannot be retrieved.
+	fn resolve_code(&self, cache: proc_macro2::TokenStream, prefix: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		let key_string = match &self.key {
+			Some(key) => key.value(),
+			None => self.identifier.to_string(),
 		};
 
-		let conversion_code = item.types.iter().map(
+		let type_first = &self.first;
+
+		let type_final = self.type_final();
+
+		// Variables with a value default go through the conversion chain as if they were optional.
+		let optional_chain = self.optional || matches!(self.default, Some(ConfigDefault::Value(_)));
+
+		let conversion_code = self.types.iter().map(
 			|ConfigPair { r#type, conversion }| {
 				let typ = r#type;
-				match (conversion, item.optional) {
+				let symbol = conversion.symbol();
+				// The error is boxed as a `std::error::Error` if possible, so that it can be walked via `source()`.
+				let conversion_error = quote! {
+					micronfig::Error::Conversion {
+						key: key.clone(),
+						conversion: #symbol,
+						target: std::any::type_name::<#typ>(),
+						source: {
+							use micronfig::error::{FromDebug as _, FromError as _};
+							(&mut &mut micronfig::error::Autoref(Some(err))).conversion_error()
+						},
+						provenance: provenance.clone().map(Box::new),
+					}
+				};
+				match (conversion, optional_chain) {
 					(Conversion::From, true) => quote! {
 						let value: Option<#typ> = value
 							.map(|v| v.into());
 					},
 					(Conversion::TryFrom, true) => quote! {
+						let value: Option<Result<#typ, _>> = value
+							.map(|v| v.try_into());
 						let value: Option<#typ> = value
-							.map(|v| v
-								.try_into()
-								.unwrap_or_else(|err| panic!("{}: Couldn't perform `=> {:?}` conversion: {:#?}", #identifier_string, std::any::type_name::<#typ>(), err))
-							);
+							.transpose()
+							.map_err(|err| #conversion_error)?;
 					},
 					(Conversion::FromStr, true) => quote! {
+						let value: Option<Result<#typ, _>> = value
+							.map(|v| v.parse());
 						let value: Option<#typ> = value
-							.map(|v| v
-								.parse()
-								.unwrap_or_else(|err| panic!("{}: Couldn't perform `> {:?}` conversion: {:#?}", #identifier_string, std::any::type_name::<#typ>(), err))
-							);
+							.transpose()
+							.map_err(|err| #conversion_error)?;
 					},
 					(Conversion::From, false) => quote! {
 						let value: #typ = value
 							.into();
 					},
 					(Conversion::TryFrom, false) => quote! {
+						let value: Result<#typ, _> = value
+							.try_into();
 						let value: #typ = value
-							.try_into()
-							.unwrap_or_else(|err| panic!("{}: Couldn't perform `=> {:?}` conversion: {:#?}", #identifier_string, std::any::type_name::<#typ>(), err));
+							.map_err(|err| #conversion_error)?;
 					},
 					(Conversion::FromStr, false) => quote! {
+						let value: Result<#typ, _> = value
+							.parse();
 						let value: #typ = value
-							.parse()
-							.unwrap_or_else(|err| panic!("{}: Couldn't perform `> {:?}` conversion: {:#?}", #identifier_string, std::any::type_name::<#typ>(), err));
+							.map_err(|err| #conversion_error)?;
+					},
+					(Conversion::Function(function), true) => quote! {
+						let value: Option<#typ> = value
+							.map(#function);
+					},
+					(Conversion::TryFunction(function), true) => quote! {
+						let value: Option<Result<#typ, _>> = value
+							.map(#function);
+						let value: Option<#typ> = value
+							.transpose()
+							.map_err(|err| #conversion_error)?;
+					},
+					(Conversion::Function(function), false) => quote! {
+						let value: #typ = (#function)(value);
+					},
+					(Conversion::TryFunction(function), false) => quote! {
+						let value: Result<#typ, _> = (#function)(value);
+						let value: #typ = value
+							.map_err(|err| #conversion_error)?;
 					},
 				}
 			}
@@ -166,28 +456,413 @@ pub fn config(input: TokenStream) -> TokenStream {
 			quote! { #acc #new }
 		});
 
-		let require_code = match item.optional {
-			true => quote! {},
+		let require_code = match (self.optional, &self.default) {
+			(true, _) => quote! {},
+			(false, Some(ConfigDefault::Value(_))) => quote! {},
+			(false, Some(ConfigDefault::Raw(lit))) => quote! {
+				let provenance: Option<micronfig::source::Provenance> = provenance
+					.or_else(|| Some(micronfig::source::Provenance::default_value()));
+				let value: #type_first = value
+					.unwrap_or_else(|| #lit.into());
+			},
+			(false, None) => quote! {
+				let value: #type_first = value
+					.ok_or_else(|| micronfig::Error::Missing { key: key.clone(), checked: #cache.provenances() })?;
+			},
+		};
+
+		let default_code = match &self.default {
+			Some(ConfigDefault::Value(expr)) => quote! {
+				let provenance: Option<micronfig::source::Provenance> = provenance
+					.or_else(|| Some(micronfig::source::Provenance::default_value()));
+				let value: #type_final = value
+					.unwrap_or_else(|| #expr);
+			},
+			_ => quote! {},
+		};
+
+		let constraint_code = self.constraints.iter().map(|constraint| {
+			let check = constraint.check();
+			let description = constraint.description();
+
+			quote! {
+				if !#check {
+					return Err(micronfig::Error::Constraint { key: key.clone(), constraint: #description, provenance: provenance.clone().map(Box::new) });
+				}
+			}
+		});
+
+		let constraints_code = match self.optional {
+			true => quote! {
+				if let Some(value) = value.as_ref() {
+					#( #constraint_code )*
+				}
+			},
 			false => quote! {
-				let value: String = value
-					.unwrap_or_else(|| panic!("{}: Is required, but has no value set", #identifier_string));
+				{
+					let value = &value;
+					#( #constraint_code )*
+				}
+			},
+		};
+
+		let trim_code = match &self.trim {
+			Some(trim) => {
+				let trim_code = trim.trim_code();
+				quote! { Some(#trim_code) }
 			},
+			None => quote! { None },
 		};
 
 		quote! {
+			{
+				let key: std::string::String = format!("{}{}", #prefix, #key_string);
+				let (value, provenance): (Option<#type_first>, Option<micronfig::source::Provenance>) = match #cache.try_lookup_as(key.as_ref(), #trim_code)? {
+					Some((value, provenance)) => (Some(value), Some(provenance)),
+					None => (None, None),
+				};
+
+				#require_code
+				#conversion_code
+				#default_code
+				#constraints_code
+
+				(value, provenance)
+			}
+		}
+	}
+
+	/// The attributes which should be applied to every code referring to the generated functions.
+	fn cfg_attributes(&self) -> impl Iterator<Item = &Attribute> {
+		self.attributes.iter()
+			.filter(|attribute| attribute.path().is_ident("cfg"))
+	}
+
+	/// The attributes which should be applied to the fields of the generated instance struct.
+	fn field_attributes(&self) -> impl Iterator<Item = &Attribute> {
+		self.attributes.iter()
+			.filter(|attribute| attribute.path().is_ident("cfg") || attribute.path().is_ident("doc"))
+	}
+}
+
+impl Parse for ConfigDefault {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		match input.parse::<Expr>()? {
+			Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Ok(ConfigDefault::Raw(lit)),
+			expr => Ok(ConfigDefault::Value(expr)),
+		}
+	}
+}
+
+impl Parse for ConfigConstraint {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		// Open ranges would otherwise try to parse the following `where` as their end.
+		let tokens = input.step(|cursor| {
+			let mut tokens = proc_macro2::TokenStream::new();
+			let mut rest = *cursor;
+
+			while let Some((tree, next)) = rest.token_tree() {
+				match &tree {
+					proc_macro2::TokenTree::Ident(ident) if ident == "where" => break,
+					proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => break,
+					_ => {},
+				}
+
+				tokens.extend([tree]);
+				rest = next;
+			}
+
+			Ok((tokens, rest))
+		})?;
+
+		match syn::parse2::<Expr>(tokens)? {
+			Expr::Range(range) => Ok(ConfigConstraint::Range(range)),
+			Expr::Array(array) => Ok(ConfigConstraint::Set(array)),
+			Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Ok(ConfigConstraint::Pattern(validate_pattern(lit)?)),
+			expr => Ok(ConfigConstraint::Predicate(expr)),
+		}
+	}
+}
+
+/// Check that the given string literal contains a valid regular expression, so that it never fails to compile at runtime.
+#[cfg(feature = "regex")]
+fn validate_pattern(lit: LitStr) -> syn::Result<LitStr> {
+	match regex::Regex::new(&lit.value()) {
+		Ok(_) => Ok(lit),
+		Err(err) => Err(syn::Error::new_spanned(lit, format!("invalid regular expression: {err}"))),
+	}
+}
+
+/// Pattern constraints cannot be used without the `regex` feature, as their regular expressions cannot be compiled.
+#[cfg(not(feature = "regex"))]
+fn validate_pattern(lit: LitStr) -> syn::Result<LitStr> {
+	Err(syn::Error::new_spanned(lit, "pattern constraints require the `regex` feature of `micronfig` to be enabled."))
+}
+
+impl ConfigConstraint {
+	/// An expression evaluating to whether `value`, a reference to the final value, satisfies the constraint.
+	fn check(&self) -> proc_macro2::TokenStream {
+		match self {
+			ConfigConstraint::Range(range) => quote! {
+				std::ops::RangeBounds::contains(&(#range), value)
+			},
+			ConfigConstraint::Set(array) => quote! {
+				#array.iter().any(|allowed| value == allowed)
+			},
+			ConfigConstraint::Pattern(pattern) => quote! {
+				{
+					static PATTERN: micronfig::constraints::Pattern = micronfig::constraints::Pattern::new(#pattern);
+					PATTERN.is_match(value.as_ref())
+				}
+			},
+			ConfigConstraint::Predicate(predicate) => quote! {
+				micronfig::constraints::satisfies(value, #predicate)
+			},
+		}
+	}
+
+	/// A string representation of the constraint, used in error messages.
+	fn description(&self) -> String {
+		match self {
+			ConfigConstraint::Range(range) => quote!(#range).to_string(),
+			ConfigConstraint::Set(array) => quote!(#array).to_string(),
+			ConfigConstraint::Pattern(pattern) => quote!(#pattern).to_string(),
+			ConfigConstraint::Predicate(predicate) => quote!(#predicate).to_string(),
+		}
+	}
+}
+
+impl ConfigPair {
+	/// Check whether the next tokens are the start of a [`ConfigPair`].
+	fn peek(input: ParseStream) -> bool {
+		input.peek(Token![->]) || input.peek(Token![=>]) || input.peek(Token![>]) || (input.peek(Token![|]) && input.peek2(Token![>]))
+	}
+}
+
+impl Parse for ConfigPair {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		if input.peek(Token![|]) && input.peek2(Token![>]) {
+			input.parse::<Token![|]>()
+				.expect("Expected `|`, as it was previously peeked");
+			input.parse::<Token![>]>()
+				.expect("Expected `>`, as it was previously peeked");
+
+			let function = match input.peek(Token![|]) || input.peek(Token![||]) || input.peek(Token![move]) {
+				true => Expr::Closure(syn::parse2(closure_tokens(input)?)?),
+				false => Expr::Path(input.parse()?),
+			};
+
+			let conversion = match input.parse::<Conversion>() {
+				Ok(Conversion::From) => Conversion::Function(function),
+				Ok(Conversion::TryFrom) => Conversion::TryFunction(function),
+				_ => return Err(input.error("cannot determine the return type of the function; specify it with `-> Type` if the function returns the value, or with `=> Type` if it returns a `Result` containing the value.")),
+			};
+			let r#type = input.parse::<Type>()?;
+
+			return Ok(Self { conversion, r#type })
+		}
+
+		let conversion = input.parse::<Conversion>()?;
+		let r#type = input.parse::<Type>()?;
+
+		Ok(Self { conversion, r#type })
+	}
+}
+
+/// Collect the tokens of a closure, stopping before the first `->` or `=>` following its parameters.
+///
+/// Required because the body of a closure is an expression, which would otherwise try to parse the `->` or `=>` specifying the return type of the [`ConfigPair`].
+fn closure_tokens(input: ParseStream) -> syn::Result<proc_macro2::TokenStream> {
+	input.step(|cursor| {
+		let mut tokens = proc_macro2::TokenStream::new();
+		let mut rest = *cursor;
+		let mut pipes = 0;
+		let mut after_parameters = false;
+		let mut explicit_return = false;
+
+		while let Some((tree, next)) = rest.token_tree() {
+			let mut closes_parameters = false;
+
+			if let proc_macro2::TokenTree::Punct(punct) = &tree {
+				let arrow = matches!(punct.as_char(), '-' | '=')
+					&& punct.spacing() == proc_macro2::Spacing::Joint
+					&& next.punct().is_some_and(|(p, _)| p.as_char() == '>');
+
+				if punct.as_char() == '|' && pipes < 2 {
+					pipes += 1;
+					closes_parameters = pipes == 2;
+				}
+				// An arrow right after the parameters specifies the return type of the closure itself.
+				else if arrow && after_parameters {
+					explicit_return = true;
+				}
+				else if arrow && pipes == 2 {
+					break;
+				}
+			}
+
+			let closes_body = explicit_return && matches!(&tree, proc_macro2::TokenTree::Group(group) if group.delimiter() == proc_macro2::Delimiter::Brace);
+
+			tokens.extend([tree]);
+			rest = next;
+			after_parameters = closes_parameters;
+
+			if closes_body {
+				break;
+			}
+		}
+
+		Ok((tokens, rest))
+	})
+}
+
+impl Conversion {
+	/// The symbol the conversion is written with, as reported in [`micronfig::Error::Conversion`].
+	fn symbol(&self) -> &'static str {
+		match self {
+			Conversion::From => "->",
+			Conversion::TryFrom => "=>",
+			Conversion::FromStr => ">",
+			Conversion::Function(_) | Conversion::TryFunction(_) => "|>",
+		}
+	}
+}
+
+impl Parse for Conversion {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		if input.parse::<Token![->]>().is_ok() {
+			Ok(Conversion::From)
+		}
+		else if input.parse::<Token![=>]>().is_ok() {
+			Ok(Conversion::TryFrom)
+		}
+		else if input.parse::<Token![>]>().is_ok() {
+			Ok(Conversion::FromStr)
+		}
+		else {
+			Err(input.error("cannot determine conversion method to use; valid conversion tokens are `->` (From), `=>` (TryFrom), `>` (FromStr) and `|>` (function)."))
+		}
+	}
+}
+
+#[proc_macro]
+pub fn config(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as Config);
+
+	let envdot_defaults = input.settings.envdot_defaults.as_ref().is_none_or(|lit| lit.value);
+
+	let cache_init = match (&input.settings.envdot_discovery, input.settings.envdot.as_slice(), envdot_defaults) {
+		(None, [], true) => quote! { micronfig::cache::Cache::new },
+		(Some(discovery), [], true) => {
+			let discovery_code = discovery.discovery_code();
+			quote! { || micronfig::cache::Cache::discover(#discovery_code) }
+		},
+		(discovery, paths, defaults) => {
+			let discovery_code = discovery.as_ref().unwrap_or(&ConfigDiscovery::Cwd).discovery_code();
+
+			let cache_default_code = match defaults {
+				true => quote! { micronfig::cache::Cache::discover(discovery.clone()) },
+				false => quote! { micronfig::cache::Cache::default() },
+			};
+
+			quote! {
+				|| {
+					let discovery = #discovery_code;
+					let mut cache = #cache_default_code;
+
+					let directory = discovery.directory();
+					#( cache.envdot_register(directory.join(#paths)); )*
+
+					cache
+				}
+			}
+		},
+	};
+
+	let block_visibility = input.settings.visibility();
+
+	let cache_code = quote! {
+		static _CACHE: std::sync::OnceLock<micronfig::cache::Cache> = std::sync::OnceLock::new();
+
+		#[allow(non_snake_case)]
+		fn _cache() -> &'static micronfig::cache::Cache {
+			_CACHE.get_or_init(#cache_init)
+		}
+
+		/// Override the cache the variables of this block are retrieved from, for example with one created by a [`micronfig::cache::CacheBuilder`].
+		///
+		/// Must be called before any variable is accessed; returns the given cache as [`Err`] otherwise.
+		#[allow(dead_code)]
+		#block_visibility fn set_cache(cache: micronfig::cache::Cache) -> Result<(), micronfig::cache::Cache> {
+			_CACHE.set(cache)
+		}
+	};
+
+	let prefix_default = match &input.settings.prefix {
+		Some(prefix) => prefix.value(),
+		None => String::new(),
+	};
+
+	let prefix_code = quote! {
+		static _PREFIX: std::sync::OnceLock<std::string::String> = std::sync::OnceLock::new();
+
+		#[allow(non_snake_case)]
+		fn _prefix() -> &'static str {
+			_PREFIX.get_or_init(|| #prefix_default.to_string())
+		}
+
+		/// Override the prefix prepended to the keys of all variables of this block.
+		///
+		/// Must be called before any variable is accessed; returns the given prefix as [`Err`] otherwise.
+		#[allow(dead_code)]
+		#block_visibility fn set_prefix(prefix: impl Into<std::string::String>) -> Result<(), std::string::String> {
+			_PREFIX.set(prefix.into())
+		}
+	};
+
+	let items_code = input.items.iter().map(|item: &ConfigItem| {
+		let identifier = &item.identifier;
+		let type_final_option = item.type_final_option();
+		let resolve_code = item.resolve_code(quote! { _cache() }, quote! { _prefix() });
+
+		let resolve_identifier = format_ident!("_resolve_{}", identifier);
+		let try_identifier = format_ident!("try_{}", identifier);
+		let source_identifier = format_ident!("{}_source", identifier);
+		let attributes = &item.attributes;
+		let cfg_attributes = item.cfg_attributes();
+		let visibility = item.visibility();
+
+		quote! {
+			#( #cfg_attributes )*
 			#[allow(non_snake_case)]
-			pub(crate) fn #identifier() -> &'static #type_final_option {
-				static LOCK: std::sync::OnceLock<#type_final_option> = std::sync::OnceLock::new();
-				
+			fn #resolve_identifier() -> &'static Result<(#type_final_option, Option<micronfig::source::Provenance>), micronfig::Error> {
+				static LOCK: std::sync::OnceLock<Result<(#type_final_option, Option<micronfig::source::Provenance>), micronfig::Error>> = std::sync::OnceLock::new();
+
 				LOCK.get_or_init(|| {
-					let key = #identifier_string.as_ref();
-					let value: Option<#type_first> = _cache().get(key);
+					Ok(#resolve_code)
+				})
+			}
 
-					#require_code
-					#conversion_code
+			#( #attributes )*
+			#[allow(non_snake_case)]
+			#visibility fn #try_identifier() -> Result<&'static #type_final_option, &'static micronfig::Error> {
+				#resolve_identifier().as_ref()
+					.map(|(value, _)| value)
+			}
 
-					value
-				})
+			#( #attributes )*
+			#[allow(non_snake_case)]
+			#visibility fn #source_identifier() -> Option<&'static micronfig::source::Provenance> {
+				#resolve_identifier().as_ref()
+					.ok()
+					.and_then(|(_, provenance)| provenance.as_ref())
+			}
+
+			#( #attributes )*
+			#[allow(non_snake_case, deprecated)]
+			#visibility fn #identifier() -> &'static #type_final_option {
+				#try_identifier()
+					.unwrap_or_else(|err| panic!("{}", err))
 			}
 		}
 	}).reduce(|acc, new| {
@@ -197,10 +872,97 @@ pub fn config(input: TokenStream) -> TokenStream {
 		}
 	});
 
+	let validate_items_code = input.items.iter().map(|item: &ConfigItem| {
+		let try_identifier = format_ident!("try_{}", item.identifier);
+		let cfg_attributes = item.cfg_attributes();
+
+		quote! {
+			#( #cfg_attributes )*
+			if let Err(err) = #try_identifier() {
+				report.errors.push(err);
+			}
+		}
+	});
+
+	let validate_code = quote! {
+		/// Retrieve the values of all variables of this block, collecting every error encountered into a [`micronfig::error::Report`].
+		#[allow(dead_code, deprecated)]
+		#block_visibility fn validate() -> Result<(), micronfig::error::Report> {
+			let mut report = micronfig::error::Report::default();
+
+			#( #validate_items_code )*
+
+			report.into_result()
+		}
+	};
+
+	let instance_code = match &input.settings.instance {
+		None => quote! {},
+		Some(ConfigInstance { attributes, visibility, identifier }) => {
+			let fields_code = input.items.iter().map(|item: &ConfigItem| {
+				let field_attributes = item.field_attributes();
+				let visibility = item.visibility();
+				let identifier = &item.identifier;
+				let type_final_option = item.type_final_option();
+
+				quote! {
+					#( #field_attributes )*
+					#visibility #identifier: #type_final_option,
+				}
+			});
+
+			let values_code = input.items.iter().map(|item: &ConfigItem| {
+				let cfg_attributes = item.cfg_attributes();
+				let identifier = &item.identifier;
+				let resolve_code = item.resolve_code(quote! { cache }, quote! { _prefix() });
+
+				quote! {
+					#( #cfg_attributes )*
+					#identifier: #resolve_code.0,
+				}
+			});
+
+			let visibility = match visibility {
+				Visibility::Inherited => syn::parse_quote!(pub(crate)),
+				visibility => visibility.clone(),
+			};
+
+			quote! {
+				#( #attributes )*
+				#[allow(non_snake_case)]
+				#visibility struct #identifier {
+					#( #fields_code )*
+				}
+
+				impl #identifier {
+					/// Retrieve the values of all variables of this block from the given [`micronfig::cache::Cache`], returning the first error encountered.
+					#[allow(dead_code, deprecated)]
+					#visibility fn from_cache(cache: &micronfig::cache::Cache) -> Result<Self, micronfig::Error> {
+						Ok(Self {
+							#( #values_code )*
+						})
+					}
+				}
+			}
+		},
+	};
+
 	let quote = quote! {
 		#cache_code
+		#prefix_code
 		#items_code
+		#validate_code
+		#instance_code
 	};
 
 	quote.into()
-}
\ No newline at end of file
+}
+
+#[proc_macro_derive(Config, attributes(config))]
+pub fn derive_config(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as syn::DeriveInput);
+
+	derive::expand(input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
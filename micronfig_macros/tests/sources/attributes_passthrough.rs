@@ -0,0 +1,20 @@
+#![deny(warnings)]
+
+micronfig::config! {
+	/// The garas to use.
+	///
+	/// Has no default value.
+	GARAS,
+	#[cfg(any())]
+	AUTO,
+	#[deprecated = "use GARAS instead"]
+	BUS?,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	std::env::set_var("AUTO", "auto");
+
+	assert_eq!(GARAS(), "garas");
+	assert!(validate().is_ok());
+}
@@ -0,0 +1,26 @@
+use std::io::Write;
+
+micronfig::config! {
+	GARAS,
+	AUTO?,
+}
+
+fn main() {
+	let path = std::env::temp_dir().join(format!("micronfig_cache_override_{}.env", std::process::id()));
+	let mut file = std::fs::File::create(&path).unwrap();
+	write!(file, "GARAS=envdot\nAUTO=envdot\n").unwrap();
+
+	std::env::set_var("GARAS", "envvars");
+	std::env::remove_var("AUTO");
+
+	let cache = micronfig::cache::Cache::builder()
+		.envdot(&path)
+		.envvars()
+		.build();
+	set_cache(cache).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert_eq!(GARAS(), "envdot");
+	assert_eq!(AUTO(), &Some("envdot".to_string()));
+	assert!(set_cache(micronfig::cache::Cache::builder().build()).is_err());
+}
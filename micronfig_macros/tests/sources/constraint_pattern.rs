@@ -0,0 +1,12 @@
+micronfig::config! {
+	GARAS where "^[a-z]+$",
+	AUTO where "^[a-z]+$",
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	std::env::set_var("AUTO", "AUTO");
+
+	assert_eq!(GARAS(), "garas");
+	assert!(matches!(try_AUTO(), Err(micronfig::Error::Constraint { .. })));
+}
@@ -0,0 +1,19 @@
+fn is_even(value: &u64) -> bool {
+	value % 2 == 0
+}
+
+micronfig::config! {
+	GARAS: String > u64 where crate::is_even,
+	AUTO?: String > u64 where crate::is_even where |value| *value > 10,
+	BUS where |value: &String| !value.is_empty(),
+}
+
+fn main() {
+	std::env::set_var("GARAS", "2");
+	std::env::set_var("AUTO", "4");
+	std::env::set_var("BUS", "");
+
+	assert_eq!(GARAS(), &2u64);
+	assert!(matches!(try_AUTO(), Err(micronfig::Error::Constraint { .. })));
+	assert!(matches!(try_BUS(), Err(micronfig::Error::Constraint { .. })));
+}
@@ -0,0 +1,18 @@
+micronfig::config! {
+	GARAS: String > u16 where 1024..=65535,
+	AUTO: String > u16 where 1024.. where |value| *value != 8080,
+	BUS?: String > u16 where ..1024,
+	TRAM: String > u16 = 8080 where 1024..=65535,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "8080");
+	std::env::set_var("AUTO", "80");
+	std::env::set_var("BUS", "80");
+	std::env::remove_var("TRAM");
+
+	assert_eq!(GARAS(), &8080u16);
+	assert!(matches!(try_AUTO(), Err(micronfig::Error::Constraint { .. })));
+	assert_eq!(BUS(), &Some(80u16));
+	assert_eq!(TRAM(), &8080u16);
+}
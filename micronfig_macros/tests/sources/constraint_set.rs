@@ -0,0 +1,15 @@
+micronfig::config! {
+	GARAS where ["debug", "info", "warn"],
+	AUTO where ["debug", "info", "warn"],
+	BUS: String > u8 where [1, 2, 4, 8],
+}
+
+fn main() {
+	std::env::set_var("GARAS", "info");
+	std::env::set_var("AUTO", "trace");
+	std::env::set_var("BUS", "4");
+
+	assert_eq!(GARAS(), "info");
+	assert!(matches!(try_AUTO(), Err(micronfig::Error::Constraint { constraint, .. }) if constraint.contains(r#""warn""#)));
+	assert_eq!(BUS(), &4u8);
+}
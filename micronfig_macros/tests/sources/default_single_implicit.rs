@@ -0,0 +1,8 @@
+micronfig::config! {
+	GARASAUTO = "garasauto",
+}
+
+fn main() {
+	std::env::remove_var("GARASAUTO");
+	assert_eq!(GARASAUTO(), "garasauto");
+}
@@ -0,0 +1,8 @@
+micronfig::config! {
+	GARASAUTO: String > u16 = "8080",
+}
+
+fn main() {
+	std::env::remove_var("GARASAUTO");
+	assert_eq!(GARASAUTO(), &8080u16);
+}
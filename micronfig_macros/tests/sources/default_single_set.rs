@@ -0,0 +1,8 @@
+micronfig::config! {
+	GARASAUTO: String > u16 = 8080,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "1234");
+	assert_eq!(GARASAUTO(), &1234u16);
+}
@@ -0,0 +1,34 @@
+#[derive(Debug, micronfig::Config)]
+#[config(prefix = "DERIVE_CONSTRAINT_")]
+#[allow(dead_code)]
+struct Settings {
+	#[config(where = 1024..=65535)]
+	port: u16,
+	#[config(where = ["debug", "info", "warn"], default = "info")]
+	level: String,
+	#[config(where = "^[a-z]+$", where = |value: &String| value.len() < 8)]
+	name: String,
+}
+
+fn main() {
+	std::env::set_var("DERIVE_CONSTRAINT_PORT", "8080");
+	std::env::remove_var("DERIVE_CONSTRAINT_LEVEL");
+	std::env::set_var("DERIVE_CONSTRAINT_NAME", "garas");
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = Settings::from_cache(&cache).unwrap();
+	assert_eq!(settings.port, 8080);
+	assert_eq!(settings.level, "info");
+
+	std::env::set_var("DERIVE_CONSTRAINT_PORT", "80");
+	let cache = micronfig::cache::Cache::new();
+	let error = Settings::from_cache(&cache).unwrap_err();
+	assert!(matches!(error, micronfig::Error::Constraint { .. }));
+	assert_eq!(error.key(), "DERIVE_CONSTRAINT_PORT");
+
+	std::env::set_var("DERIVE_CONSTRAINT_PORT", "8080");
+	std::env::set_var("DERIVE_CONSTRAINT_NAME", "garasauto");
+	let cache = micronfig::cache::Cache::new();
+	let error = Settings::from_cache(&cache).unwrap_err();
+	assert_eq!(error.key(), "DERIVE_CONSTRAINT_NAME");
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq)]
+struct GuildId(u64);
+
+impl From<u64> for GuildId {
+	fn from(value: u64) -> Self {
+		Self(value)
+	}
+}
+
+#[derive(micronfig::Config)]
+#[config(prefix = "ANGY_")]
+pub struct Settings {
+	#[config(conversion = String > u16, default = 8080)]
+	port: u16,
+	#[config(conversion = String > u64 -> GuildId, optional)]
+	guild_id: Option<GuildId>,
+	#[config(conversion = String > u64 |> Duration::from_secs -> Duration, default = "60")]
+	timeout: Duration,
+}
+
+fn main() {
+	std::env::remove_var("ANGY_PORT");
+	std::env::set_var("ANGY_GUILD_ID", "1234");
+	std::env::remove_var("ANGY_TIMEOUT");
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = Settings::from_cache(&cache).unwrap();
+
+	assert_eq!(settings.port, 8080);
+	assert_eq!(settings.guild_id, Some(GuildId(1234)));
+	assert_eq!(settings.timeout, Duration::from_secs(60));
+}
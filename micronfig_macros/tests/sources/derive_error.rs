@@ -0,0 +1,19 @@
+#[derive(micronfig::Config)]
+struct Settings {
+	#[allow(dead_code)]
+	garas: String,
+	#[config(conversion = String > u64)]
+	#[allow(dead_code)]
+	auto: u64,
+}
+
+fn main() {
+	std::env::remove_var("GARAS");
+	std::env::set_var("AUTO", "auto");
+
+	let cache = micronfig::cache::Cache::new();
+	let error = Settings::from_cache(&cache).err().unwrap();
+
+	assert!(matches!(error, micronfig::Error::Missing { .. }));
+	assert_eq!(error.key(), "GARAS");
+}
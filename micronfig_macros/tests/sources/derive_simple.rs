@@ -0,0 +1,21 @@
+#[derive(micronfig::Config)]
+struct Settings {
+	garas: String,
+	#[config(key = "AUTO_RENAMED")]
+	auto: String,
+	#[config(optional)]
+	bus: Option<String>,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	std::env::set_var("AUTO_RENAMED", "auto");
+	std::env::remove_var("BUS");
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = Settings::from_cache(&cache).unwrap();
+
+	assert_eq!(settings.garas, "garas");
+	assert_eq!(settings.auto, "auto");
+	assert_eq!(settings.bus, None);
+}
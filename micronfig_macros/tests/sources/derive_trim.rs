@@ -0,0 +1,19 @@
+#[derive(micronfig::Config)]
+struct Settings {
+	#[config(key = "DERIVE_TRIM_PASSWORD", trim = "whitespace")]
+	password: String,
+}
+
+fn main() {
+	let path = std::env::temp_dir().join(format!("micronfig_derive_trim_{}.txt", std::process::id()));
+	std::fs::write(&path, "\thunter2 \r\n").unwrap();
+
+	std::env::remove_var("DERIVE_TRIM_PASSWORD");
+	std::env::set_var("DERIVE_TRIM_PASSWORD_FILE", &path);
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = Settings::from_cache(&cache).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert_eq!(settings.password, "hunter2");
+}
@@ -0,0 +1,31 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(micronfig::Config)]
+struct Settings {
+	name: String,
+	port: u16,
+	#[config(default = 60)]
+	timeout: u64,
+	#[config(optional)]
+	listen: Option<SocketAddr>,
+	#[config(optional)]
+	data: std::option::Option<PathBuf>,
+}
+
+fn main() {
+	std::env::set_var("NAME", "garas");
+	std::env::set_var("PORT", "8080");
+	std::env::remove_var("TIMEOUT");
+	std::env::set_var("LISTEN", "127.0.0.1:8080");
+	std::env::remove_var("DATA");
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = Settings::from_cache(&cache).unwrap();
+
+	assert_eq!(settings.name, "garas");
+	assert_eq!(settings.port, 8080);
+	assert_eq!(settings.timeout, 60);
+	assert_eq!(settings.listen, Some("127.0.0.1:8080".parse().unwrap()));
+	assert_eq!(settings.data, None);
+}
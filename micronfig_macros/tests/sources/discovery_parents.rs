@@ -0,0 +1,21 @@
+micronfig::config! {
+	#![envdot_discovery = "parents"]
+
+	MICRONFIG_DISCOVERY_PARENTS,
+}
+
+fn main() {
+	let directory = std::env::temp_dir().join(format!("micronfig_discovery_parents_{}", std::process::id()));
+	let nested = directory.join("nested/deeper");
+	std::fs::create_dir_all(&nested).unwrap();
+	std::fs::write(directory.join(".env"), "MICRONFIG_DISCOVERY_PARENTS=parents\n").unwrap();
+	std::env::set_current_dir(&nested).unwrap();
+	std::env::remove_var("MICRONFIG_DISCOVERY_PARENTS");
+
+	let value = MICRONFIG_DISCOVERY_PARENTS();
+	let source = MICRONFIG_DISCOVERY_PARENTS_source().unwrap().path.clone();
+	std::fs::remove_dir_all(&directory).unwrap();
+
+	assert_eq!(value, "parents");
+	assert_eq!(source.as_deref(), Some(directory.join(".env").as_path()));
+}
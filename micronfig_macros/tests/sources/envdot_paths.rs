@@ -0,0 +1,26 @@
+micronfig::config! {
+	#![envdot_defaults = false]
+	#![envdot = "config/paths.env"]
+	#![envdot = "config/shared.env"]
+
+	MICRONFIG_ENVDOT_PATHS_LOCAL,
+	MICRONFIG_ENVDOT_PATHS_SHARED,
+}
+
+fn main() {
+	let directory = std::env::temp_dir().join(format!("micronfig_envdot_paths_{}", std::process::id()));
+	std::fs::create_dir_all(directory.join("config")).unwrap();
+	std::fs::write(directory.join(".env.local"), "MICRONFIG_ENVDOT_PATHS_LOCAL=local\n").unwrap();
+	std::fs::write(directory.join("config/paths.env"), "MICRONFIG_ENVDOT_PATHS_LOCAL=paths\n").unwrap();
+	std::fs::write(directory.join("config/shared.env"), "MICRONFIG_ENVDOT_PATHS_LOCAL=shared\nMICRONFIG_ENVDOT_PATHS_SHARED=shared\n").unwrap();
+	std::env::set_current_dir(&directory).unwrap();
+	std::env::remove_var("MICRONFIG_ENVDOT_PATHS_LOCAL");
+	std::env::remove_var("MICRONFIG_ENVDOT_PATHS_SHARED");
+
+	let local = MICRONFIG_ENVDOT_PATHS_LOCAL();
+	let shared = MICRONFIG_ENVDOT_PATHS_SHARED();
+	std::fs::remove_dir_all(&directory).unwrap();
+
+	assert_eq!(local, "paths");
+	assert_eq!(shared, "shared");
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Timeout(Duration);
+
+impl From<Duration> for Timeout {
+	fn from(value: Duration) -> Self {
+		Self(value)
+	}
+}
+
+micronfig::config! {
+	GARASAUTO?: String > u64 |> Duration::from_millis -> Duration -> crate::Timeout,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "500");
+	assert_eq!(GARASAUTO(), &Some(Timeout(Duration::from_millis(500))));
+}
@@ -0,0 +1,18 @@
+micronfig::config! {
+	GARAS: String > u64 |> |minutes| minutes * 60 -> u64,
+	AUTO: String > u64 |> |minutes: u64| -> u64 { minutes * 60 } -> u64,
+	BUS: String |> move |value: String| value.len() -> usize,
+	TRAM: String |> |_| 0 -> u8,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "2");
+	std::env::set_var("AUTO", "3");
+	std::env::set_var("BUS", "bus");
+	std::env::set_var("TRAM", "tram");
+
+	assert_eq!(GARAS(), &120u64);
+	assert_eq!(AUTO(), &180u64);
+	assert_eq!(BUS(), &3usize);
+	assert_eq!(TRAM(), &0u8);
+}
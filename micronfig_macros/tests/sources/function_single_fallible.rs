@@ -0,0 +1,19 @@
+fn parse_hex(value: String) -> Result<u32, std::num::ParseIntError> {
+	u32::from_str_radix(&value, 16)
+}
+
+micronfig::config! {
+	GARAS: String |> crate::parse_hex => u32,
+	AUTO?: String |> crate::parse_hex => u32,
+	BUS: String |> crate::parse_hex => u32,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "ff");
+	std::env::set_var("AUTO", "10");
+	std::env::set_var("BUS", "zz");
+
+	assert_eq!(GARAS(), &255u32);
+	assert_eq!(AUTO(), &Some(16u32));
+	assert!(matches!(try_BUS(), Err(micronfig::Error::Conversion { conversion: "|>", .. })));
+}
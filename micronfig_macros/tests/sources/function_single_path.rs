@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+micronfig::config! {
+	GARASAUTO: String > u64 |> Duration::from_secs -> Duration,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "60");
+	assert_eq!(GARASAUTO(), &Duration::from_secs(60));
+}
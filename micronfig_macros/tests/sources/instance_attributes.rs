@@ -0,0 +1,26 @@
+#![deny(warnings)]
+
+mod config {
+	micronfig::config! {
+		#![prefix = "GARAS_"]
+		#![instance(#[derive(Debug, Clone)] pub Settings)]
+
+		/// The auto.
+		pub auto as "AUTO",
+		#[cfg(any())]
+		pub bus as "BUS",
+		pub tram as "TRAM": String > u16 = 8080 where 1024..,
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS_AUTO", "auto");
+	std::env::remove_var("GARAS_TRAM");
+
+	let cache = micronfig::cache::Cache::new();
+	let settings = config::Settings::from_cache(&cache).unwrap().clone();
+
+	assert_eq!(settings.auto, "auto");
+	assert_eq!(settings.tram, 8080);
+	assert_eq!(config::auto(), "auto");
+}
@@ -0,0 +1,42 @@
+use std::io::Write;
+
+micronfig::config! {
+	#![instance(Tenant)]
+
+	GARAS,
+	AUTO: String > u64,
+	BUS?,
+}
+
+fn dotenv(name: &str, contents: &str) -> std::path::PathBuf {
+	let path = std::env::temp_dir().join(format!("{name}_{}.env", std::process::id()));
+	let mut file = std::fs::File::create(&path).unwrap();
+	write!(file, "{contents}").unwrap();
+	path
+}
+
+fn main() {
+	std::env::remove_var("GARAS");
+	std::env::remove_var("AUTO");
+	std::env::remove_var("BUS");
+
+	let first_path = dotenv("micronfig_instance_multiple_first", "GARAS=first\nAUTO=1\n");
+	let second_path = dotenv("micronfig_instance_multiple_second", "GARAS=second\nAUTO=2\nBUS=bus\n");
+
+	let mut first = micronfig::cache::Cache::default();
+	first.envdot_register(&first_path);
+	let mut second = micronfig::cache::Cache::default();
+	second.envdot_register(&second_path);
+	std::fs::remove_file(&first_path).unwrap();
+	std::fs::remove_file(&second_path).unwrap();
+
+	let first = Tenant::from_cache(&first).unwrap();
+	let second = Tenant::from_cache(&second).unwrap();
+
+	assert_eq!(first.GARAS, "first");
+	assert_eq!(first.AUTO, 1);
+	assert_eq!(first.BUS, None);
+	assert_eq!(second.GARAS, "second");
+	assert_eq!(second.AUTO, 2);
+	assert_eq!(second.BUS, Some("bus".to_string()));
+}
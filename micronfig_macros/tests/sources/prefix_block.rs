@@ -0,0 +1,16 @@
+micronfig::config! {
+	#![prefix = "GARAS_"]
+
+	AUTO,
+	bus as "BUS": String > u64,
+}
+
+fn main() {
+	std::env::set_var("GARAS_AUTO", "auto");
+	std::env::set_var("GARAS_BUS", "1");
+	std::env::set_var("AUTO", "wrong");
+	std::env::set_var("BUS", "2");
+
+	assert_eq!(AUTO(), "auto");
+	assert_eq!(bus(), &1u64);
+}
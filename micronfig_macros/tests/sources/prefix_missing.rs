@@ -0,0 +1,12 @@
+micronfig::config! {
+	#![prefix = "GARAS_"]
+
+	AUTO,
+}
+
+fn main() {
+	std::env::remove_var("GARAS_AUTO");
+	std::env::set_var("AUTO", "wrong");
+
+	assert_eq!(try_AUTO().unwrap_err().key(), "GARAS_AUTO");
+}
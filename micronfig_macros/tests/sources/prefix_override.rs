@@ -0,0 +1,14 @@
+micronfig::config! {
+	#![prefix = "GARAS_"]
+
+	AUTO,
+}
+
+fn main() {
+	std::env::set_var("GARAS_AUTO", "wrong");
+	std::env::set_var("TRAM_AUTO", "auto");
+
+	assert_eq!(set_prefix("TRAM_"), Ok(()));
+	assert_eq!(AUTO(), "auto");
+	assert_eq!(set_prefix("BUS_"), Err("BUS_".to_string()));
+}
@@ -0,0 +1,16 @@
+mod config {
+	micronfig::config! {
+		#![prefix = "GARAS_"]
+		#![visibility(pub)]
+
+		pub AUTO,
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS_AUTO", "wrong");
+	std::env::set_var("TRAM_AUTO", "auto");
+
+	assert_eq!(config::set_prefix("TRAM_"), Ok(()));
+	assert_eq!(config::AUTO(), "auto");
+}
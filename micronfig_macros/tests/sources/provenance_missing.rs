@@ -0,0 +1,17 @@
+micronfig::config! {
+	GARAS,
+}
+
+fn main() {
+	std::env::remove_var("GARAS");
+	std::env::remove_var("GARAS_FILE");
+
+	let cache = micronfig::cache::Cache::builder()
+		.envfiles()
+		.envvars()
+		.build();
+	set_cache(cache).unwrap();
+
+	let error = try_GARAS().unwrap_err();
+	assert_eq!(error.to_string(), "GARAS: Is required, but has no value set in any of: envfiles, envvars");
+}
@@ -0,0 +1,42 @@
+use std::io::Write;
+
+use micronfig::source::Provenance;
+
+micronfig::config! {
+	GARAS,
+	AUTO: String > u32,
+	BUS = "bus",
+	TRAM?,
+}
+
+fn main() {
+	let path = std::env::temp_dir().join(format!("micronfig_provenance_source_{}.env", std::process::id()));
+	let mut file = std::fs::File::create(&path).unwrap();
+	write!(file, "# comment\nGARAS=envdot\nAUTO=many\n").unwrap();
+
+	std::env::remove_var("GARAS");
+	std::env::remove_var("AUTO");
+	std::env::remove_var("BUS");
+	std::env::remove_var("TRAM");
+
+	let cache = micronfig::cache::Cache::builder()
+		.envvars()
+		.envdot(&path)
+		.build();
+	set_cache(cache).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert_eq!(GARAS(), "envdot");
+	assert_eq!(GARAS_source(), Some(&Provenance::new("envdot").with_path(&path).with_line(2)));
+
+	let error = try_AUTO().unwrap_err();
+	assert_eq!(error.provenance(), Some(&Provenance::new("envdot").with_path(&path).with_line(3)));
+	assert!(error.to_string().contains(":3)]"));
+	assert_eq!(AUTO_source(), None);
+
+	assert_eq!(BUS(), "bus");
+	assert_eq!(BUS_source(), Some(&Provenance::default_value()));
+
+	assert_eq!(TRAM(), &None);
+	assert_eq!(TRAM_source(), None);
+}
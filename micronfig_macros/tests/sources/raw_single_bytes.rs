@@ -0,0 +1,21 @@
+micronfig::config! {
+	RAW_BYTES: Vec<u8>,
+	RAW_BYTES_STRING?,
+}
+
+fn main() {
+	let path = std::env::temp_dir().join(format!("micronfig_raw_single_bytes_{}.der", std::process::id()));
+	std::fs::write(&path, b"\x30\x82\xFF\x0A").unwrap();
+
+	std::env::remove_var("RAW_BYTES");
+	std::env::set_var("RAW_BYTES_FILE", &path);
+	std::env::remove_var("RAW_BYTES_STRING");
+	std::env::set_var("RAW_BYTES_STRING_FILE", &path);
+
+	assert_eq!(RAW_BYTES(), b"\x30\x82\xFF\x0A");
+
+	let error = try_RAW_BYTES_STRING().unwrap_err();
+	std::fs::remove_file(&path).unwrap();
+	assert!(matches!(error, micronfig::Error::Source { .. }));
+	assert_eq!(error.provenance().unwrap().path.as_deref(), Some(path.as_path()));
+}
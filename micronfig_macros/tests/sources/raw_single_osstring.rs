@@ -0,0 +1,13 @@
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+
+micronfig::config! {
+	RAW_OSSTRING: OsString,
+}
+
+fn main() {
+	let value = OsStr::from_bytes(b"garas\xFF");
+	std::env::set_var("RAW_OSSTRING", value);
+
+	assert_eq!(RAW_OSSTRING(), value);
+}
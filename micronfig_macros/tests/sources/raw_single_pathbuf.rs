@@ -0,0 +1,17 @@
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+micronfig::config! {
+	RAW_PATHBUF: PathBuf,
+	RAW_PATHBUF_DEFAULT: PathBuf = "/etc/garas",
+}
+
+fn main() {
+	let value = OsStr::from_bytes(b"/tmp/garas\xFF");
+	std::env::set_var("RAW_PATHBUF", value);
+	std::env::remove_var("RAW_PATHBUF_DEFAULT");
+
+	assert_eq!(RAW_PATHBUF(), Path::new(value));
+	assert_eq!(RAW_PATHBUF_DEFAULT(), Path::new("/etc/garas"));
+}
@@ -0,0 +1,19 @@
+micronfig::config! {
+	garas as "GARAS",
+	auto as "AUTO_RENAMED"?: String > u64,
+	BUS,
+	tram as "TRAM" = "tram",
+}
+
+fn main() {
+	std::env::remove_var("GARAS");
+	std::env::set_var("AUTO_RENAMED", "1");
+	std::env::remove_var("auto");
+	std::env::set_var("BUS", "bus");
+	std::env::remove_var("TRAM");
+
+	assert_eq!(validate().unwrap_err().errors[0].key(), "GARAS");
+	assert_eq!(auto(), &Some(1u64));
+	assert_eq!(BUS(), "bus");
+	assert_eq!(tram(), "tram");
+}
@@ -0,0 +1,9 @@
+micronfig::config! {
+	garas_auto as "GARASAUTO": String > u64,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "1");
+	assert_eq!(garas_auto(), &1u64);
+	assert_eq!(try_garas_auto().unwrap(), &1u64);
+}
@@ -0,0 +1,26 @@
+micronfig::config! {
+	TRIM_DEFAULT,
+	#[config(trim = "none")]
+	TRIM_NONE,
+	#[config(trim = "whitespace")]
+	TRIM_WHITESPACE,
+	/// Only the first line of the file is used.
+	#[config(trim = "first_line")]
+	TRIM_FIRST_LINE,
+}
+
+fn main() {
+	let path = std::env::temp_dir().join(format!("micronfig_trim_item_{}.txt", std::process::id()));
+	std::fs::write(&path, "  token  \nsecond line\n").unwrap();
+
+	for key in ["TRIM_DEFAULT", "TRIM_NONE", "TRIM_WHITESPACE", "TRIM_FIRST_LINE"] {
+		std::env::remove_var(key);
+		std::env::set_var(format!("{key}_FILE"), &path);
+	}
+
+	assert_eq!(TRIM_DEFAULT(), "  token  \nsecond line");
+	assert_eq!(TRIM_NONE(), "  token  \nsecond line\n");
+	assert_eq!(TRIM_WHITESPACE(), "token  \nsecond line");
+	assert_eq!(TRIM_FIRST_LINE(), "  token  ");
+	std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,13 @@
+micronfig::config! {
+	GARASAUTO: String > u64,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "garasauto");
+	let error = try_GARASAUTO().unwrap_err();
+	assert!(matches!(error, micronfig::Error::Conversion { conversion: ">", .. }));
+	assert_eq!(error.key(), "GARASAUTO");
+
+	let source = std::error::Error::source(error).expect("parse error to be the source of the conversion error");
+	assert!(source.downcast_ref::<std::num::ParseIntError>().is_some());
+}
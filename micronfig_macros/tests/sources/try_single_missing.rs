@@ -0,0 +1,10 @@
+micronfig::config! {
+	GARASAUTO: String > u64,
+}
+
+fn main() {
+	std::env::remove_var("GARASAUTO");
+	let error = try_GARASAUTO().unwrap_err();
+	assert!(matches!(error, micronfig::Error::Missing { .. }));
+	assert_eq!(error.key(), "GARASAUTO");
+}
@@ -0,0 +1,8 @@
+micronfig::config! {
+	GARASAUTO?: String > u64,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "1");
+	assert_eq!(try_GARASAUTO().unwrap(), &Some(1u64));
+}
@@ -0,0 +1,17 @@
+micronfig::config! {
+	GARAS: String > u64,
+	AUTO,
+	BUS?: String > u64,
+	TRAM = "tram",
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	std::env::remove_var("AUTO");
+	std::env::set_var("BUS", "bus");
+	std::env::remove_var("TRAM");
+
+	let report = validate().unwrap_err();
+	let keys: Vec<&str> = report.errors.iter().map(|err| err.key()).collect();
+	assert_eq!(keys, vec!["GARAS", "AUTO", "BUS"]);
+}
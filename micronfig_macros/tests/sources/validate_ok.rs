@@ -0,0 +1,12 @@
+micronfig::config! {
+	GARAS: String > u64,
+	AUTO?,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "1");
+	std::env::remove_var("AUTO");
+
+	assert!(validate().is_ok());
+	assert_eq!(GARAS(), &1u64);
+}
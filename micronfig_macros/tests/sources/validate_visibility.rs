@@ -0,0 +1,15 @@
+mod config {
+	micronfig::config! {
+		#![visibility(pub)]
+
+		pub GARAS: String > u64,
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+
+	let report = config::validate().unwrap_err();
+	assert_eq!(report.errors.len(), 1);
+	assert_eq!(report.errors[0].key(), "GARAS");
+}
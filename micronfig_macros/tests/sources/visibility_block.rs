@@ -0,0 +1,18 @@
+mod config {
+	micronfig::config! {
+		#![visibility(pub)]
+
+		pub GARAS,
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS", "envvars");
+
+	let cache = micronfig::cache::Cache::builder()
+		.build();
+	config::set_cache(cache).unwrap();
+
+	assert!(config::try_GARAS().is_err());
+	assert!(config::validate().is_err());
+}
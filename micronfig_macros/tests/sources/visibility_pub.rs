@@ -0,0 +1,22 @@
+mod config {
+	micronfig::config! {
+		pub GARAS,
+		pub(super) AUTO?,
+		pub(self) BUS = "bus",
+	}
+
+	pub fn bus() -> &'static str {
+		BUS()
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	std::env::remove_var("AUTO");
+	std::env::remove_var("BUS");
+
+	assert_eq!(config::GARAS(), "garas");
+	assert_eq!(config::try_GARAS().unwrap(), "garas");
+	assert_eq!(config::AUTO(), &None);
+	assert_eq!(config::bus(), "bus");
+}
@@ -0,0 +1,7 @@
+micronfig::config! {
+	GARAS where "^[a-z+$",
+}
+
+fn main() {
+	println!("{:#?}", GARAS());
+}
@@ -0,0 +1,7 @@
+#[derive(micronfig::Config)]
+struct Settings {
+	#[config(garas = "auto")]
+	garas: String,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(micronfig::Config)]
+enum Settings {
+	Garas,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(micronfig::Config)]
+struct Settings {
+	#[config(optional)]
+	garas: u64,
+}
+
+fn main() {}
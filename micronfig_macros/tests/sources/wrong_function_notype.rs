@@ -0,0 +1,8 @@
+micronfig::config! {
+	GARASAUTO: String > u64 |> std::time::Duration::from_secs,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "60");
+	println!("{:#?}", GARASAUTO());
+}
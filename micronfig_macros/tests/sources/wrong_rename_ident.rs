@@ -0,0 +1,8 @@
+micronfig::config! {
+	garas_auto as GARASAUTO,
+}
+
+fn main() {
+	std::env::set_var("GARASAUTO", "garasauto");
+	println!("{:#?}", garas_auto());
+}
@@ -0,0 +1,10 @@
+micronfig::config! {
+	#![envdot_defaults = "no"]
+
+	GARAS,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	println!("{:#?}", GARAS());
+}
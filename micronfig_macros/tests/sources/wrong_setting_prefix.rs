@@ -0,0 +1,10 @@
+micronfig::config! {
+	#![prefix = GARAS]
+
+	AUTO,
+}
+
+fn main() {
+	std::env::set_var("AUTO", "auto");
+	println!("{:#?}", AUTO());
+}
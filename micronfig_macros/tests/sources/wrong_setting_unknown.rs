@@ -0,0 +1,10 @@
+micronfig::config! {
+	#![garas = "GARAS_"]
+
+	AUTO,
+}
+
+fn main() {
+	std::env::set_var("AUTO", "auto");
+	println!("{:#?}", AUTO());
+}
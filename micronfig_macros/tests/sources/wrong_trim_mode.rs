@@ -0,0 +1,9 @@
+micronfig::config! {
+	#[config(trim = "everything")]
+	GARAS,
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	println!("{:#?}", GARAS());
+}
@@ -0,0 +1,10 @@
+mod config {
+	micronfig::config! {
+		pub(self) GARAS,
+	}
+}
+
+fn main() {
+	std::env::set_var("GARAS", "garas");
+	println!("{:#?}", config::GARAS());
+}
@@ -16,36 +16,95 @@ macro_rules! fail {
 	}
 }
 
+pass!(attributes_passthrough);
+pass!(cache_override);
 pass!(chain_single_down);
 pass!(chain_single_up);
+pass!(constraint_pattern);
+pass!(constraint_predicate);
+pass!(constraint_range);
+pass!(constraint_set);
+pass!(default_single_implicit);
+pass!(default_single_raw);
+pass!(default_single_set);
+pass!(default_single_value);
+pass!(derive_constraint);
+pass!(derive_conversion);
+pass!(derive_error);
+pass!(derive_simple);
+pass!(derive_trim);
+pass!(derive_types);
+pass!(discovery_parents);
 pass!(empty);
+pass!(envdot_paths);
 pass!(example_angybot);
 pass!(example_distributedarcade);
 pass!(example_patchedporobot);
 pass!(from_single_custom);
+pass!(function_chain_mixed);
+pass!(function_single_closure);
+pass!(function_single_fallible);
+pass!(function_single_path);
+pass!(instance_attributes);
+pass!(instance_multiple);
 pass!(parse_single_custom);
 pass!(parse_single_i64);
 pass!(parse_single_pathbuf);
 pass!(parse_single_u64);
 pass!(parse_single_u64_optional);
+pass!(prefix_block);
+pass!(prefix_missing);
+pass!(prefix_override);
+pass!(prefix_visibility);
+pass!(provenance_missing);
+pass!(provenance_source);
+pass!(raw_single_bytes);
+#[cfg(unix)]
+pass!(raw_single_osstring);
+#[cfg(unix)]
+pass!(raw_single_pathbuf);
+pass!(rename_multi_mixed);
+pass!(rename_single);
 pass!(string_multi_explicit);
 pass!(string_multi_implicit);
 pass!(string_multi_mixed);
 pass!(string_single_explicit);
 pass!(string_single_implicit);
+pass!(trim_item);
+pass!(try_single_invalid);
+pass!(try_single_missing);
+pass!(try_single_valid);
 pass!(tryfrom_single_custom);
 pass!(unqualified_import);
+pass!(validate_multi);
+pass!(validate_ok);
+pass!(validate_visibility);
+pass!(visibility_block);
+pass!(visibility_pub);
 
+fail!(wrong_constraint_pattern);
 fail!(wrong_conversion_longfatarrow);
 fail!(wrong_conversion_longthinarrow);
 fail!(wrong_conversion_tildearrow);
 fail!(wrong_conversion_trait_from);
 fail!(wrong_conversion_trait_fromstr);
 fail!(wrong_conversion_trait_tryfrom);
+fail!(wrong_default_optional);
+fail!(wrong_derive_attribute);
+fail!(wrong_derive_enum);
+fail!(wrong_derive_optional);
+fail!(wrong_function_notype);
 fail!(wrong_nonsense_1);
 fail!(wrong_nonsense_2);
 fail!(wrong_nonsense_3);
+fail!(wrong_rename_ident);
+fail!(wrong_setting_discovery);
+fail!(wrong_setting_envdot_defaults);
+fail!(wrong_setting_prefix);
+fail!(wrong_setting_unknown);
 fail!(wrong_start);
 fail!(wrong_syntax_colon);
 fail!(wrong_syntax_type);
+fail!(wrong_trim_mode);
 fail!(wrong_unqualified_noimport);
+fail!(wrong_visibility_private);